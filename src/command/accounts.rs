@@ -0,0 +1,337 @@
+use rust_i18n::t;
+use serenity::{
+  model::prelude::{
+    interaction::{
+      application_command::{ApplicationCommandInteraction, CommandDataOption},
+      autocomplete::AutocompleteInteraction,
+      InteractionResponseType
+    },
+    command::CommandOptionType,
+    GuildId
+  },
+  prelude::Context,
+  builder::{
+    CreateApplicationCommand,
+    CreateApplicationCommandOption,
+    CreateAutocompleteResponse,
+    CreateInteractionResponse,
+    CreateInteractionResponseData,
+    CreateInteractionResponseFollowup,
+    CreateEmbed
+  },
+  utils::Color
+};
+use tracing::log::warn;
+use anyhow::{Result, anyhow};
+
+use crate::core::{
+  utils::{EMBED_INFO_COLOR, EMBED_ERROR_COLOR},
+  oauth::TwitterClient,
+  cache::{AccessTokenCache, CacheData},
+  store::{StoredAccounts, StoredToken}
+};
+
+
+static MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+
+fn get_option<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a CommandDataOption> {
+  options.iter().find(|option: &&CommandDataOption| option.name == name)
+}
+
+fn get_account_name(token: &StoredToken, locale: &str) -> String {
+  match token.is_identified() {
+    true => format!("[@{name}](https://twitter.com/{name})", name = token.screen_name),
+    false => t!("command.accounts.list-embed.unknown", locale = locale)
+  }
+}
+
+async fn load_accounts(user_id: u64) -> Result<StoredAccounts> {
+  let accounts: StoredAccounts = match AccessTokenCache::get().request(user_id).await? {
+    Some(cache_data) => cache_data.accounts,
+    None => return Ok(StoredAccounts::default())
+  };
+
+  let token: &StoredToken = match accounts.tokens.iter().find(
+    |token: &&StoredToken| !token.is_identified()
+  ) {
+    Some(token) => token,
+    None => return Ok(accounts)
+  };
+
+  match TwitterClient::from_credential(&token.credential)?.get_me().await {
+    Ok(account) => AccessTokenCache::get().update(
+      user_id,
+      |accounts: &mut StoredAccounts| accounts.identify(&token.account_id, account.id, account.username)
+    ).await,
+    Err(why) => {
+      warn!("Identify account failed: {:?} | User: {}", why, user_id);
+      Ok(accounts)
+    }
+  }
+}
+
+fn build_list_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  accounts: &StoredAccounts,
+  guild_id: Option<GuildId>,
+  locale: &str
+) -> &'a mut CreateEmbed {
+  if accounts.tokens.is_empty() {
+    return embed
+      .color(Color::new(EMBED_ERROR_COLOR))
+      .title(t!("command.accounts.empty-embed.title", locale = locale))
+      .description(t!("command.accounts.empty-embed.description", locale = locale));
+  }
+
+  let active_id: Option<&str> = accounts
+    .select(None)
+    .map(|token: &StoredToken| token.account_id.as_ref());
+  let server_id: Option<&str> = guild_id
+    .and_then(|guild_id: GuildId| accounts.guild_accounts.get(guild_id.as_u64()))
+    .map(|account_id| account_id.as_ref());
+
+  let lines: Vec<String> = accounts.tokens
+    .iter()
+    .map(
+      |token: &StoredToken| {
+        let mut markers: Vec<String> = Vec::new();
+
+        if active_id == Some(token.account_id.as_ref()) {
+          markers.push(t!("command.accounts.list-embed.active", locale = locale));
+        }
+
+        if server_id == Some(token.account_id.as_ref()) {
+          markers.push(t!("command.accounts.list-embed.server", locale = locale));
+        }
+
+        match markers.is_empty() {
+          true => format!("• {}", get_account_name(token, locale)),
+          false => format!("• {} `{}`", get_account_name(token, locale), markers.join(", "))
+        }
+      }
+    )
+    .collect();
+
+  embed
+    .color(Color::new(EMBED_INFO_COLOR))
+    .title(t!("command.accounts.list-embed.title", locale = locale))
+    .description(
+      format!(
+        "{accounts}\n\n{description}",
+        accounts = lines.join("\n"),
+        description = t!("command.accounts.list-embed.description", locale = locale)
+      )
+    )
+}
+
+async fn use_account(
+  user_id: u64,
+  options: &[CommandDataOption],
+  guild_id: Option<GuildId>,
+  embed: &mut CreateEmbed,
+  locale: &str
+) -> Result<()> {
+  let name: &str = get_option(options, "account")
+    .and_then(|option: &CommandDataOption| option.value.as_ref()?.as_str())
+    .ok_or(anyhow!("Missing option `account`."))?;
+  let server: bool = get_option(options, "server")
+    .and_then(|option: &CommandDataOption| option.value.as_ref()?.as_bool())
+    .unwrap_or(false);
+
+  let accounts: StoredAccounts = load_accounts(user_id).await?;
+
+  let token: &StoredToken = match accounts.find_by_name(name) {
+    Some(token) => token,
+    None => {
+      embed
+        .color(Color::new(EMBED_ERROR_COLOR))
+        .title(t!("command.accounts.not-found-embed.title", locale = locale))
+        .description(t!("command.accounts.not-found-embed.description", locale = locale, account = name));
+
+      return Ok(());
+    }
+  };
+
+  let guild_id: Option<u64> = match server {
+    true => guild_id.map(|guild_id: GuildId| *guild_id.as_u64()),
+    false => None
+  };
+
+  AccessTokenCache::get().update(
+    user_id,
+    |accounts: &mut StoredAccounts| accounts.use_account(token.account_id.clone(), guild_id)
+  ).await?;
+
+  embed
+    .color(Color::new(EMBED_INFO_COLOR))
+    .title(t!("command.accounts.success-embed.title", locale = locale))
+    .description(
+      t!(
+        match guild_id {
+          Some(_) => "command.accounts.success-embed.server-description",
+          None => "command.accounts.success-embed.description"
+        },
+        locale = locale,
+        username = token.screen_name
+      )
+    );
+
+  Ok(())
+}
+
+
+pub async fn execute(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction
+) -> Result<()> {
+  let option: &CommandDataOption = interaction.data.options
+    .first()
+    .ok_or(anyhow!("Missing accounts subcommand."))?;
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      response
+        .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        .interaction_response_data(
+          |message: &mut CreateInteractionResponseData<'_>| {
+            message.ephemeral(true)
+          }
+        )
+    }
+  ).await?;
+
+  let user_id: u64 = *interaction.user.id.as_u64();
+  let mut embed: CreateEmbed = CreateEmbed::default();
+
+  match option.name.as_str() {
+    "list" => {
+      build_list_embed(
+        &mut embed,
+        &load_accounts(user_id).await?,
+        interaction.guild_id,
+        &interaction.locale
+      );
+    },
+    "use" => use_account(
+      user_id,
+      &option.options,
+      interaction.guild_id,
+      &mut embed,
+      &interaction.locale
+    ).await?,
+    name => return Err(anyhow!("Unknown accounts subcommand `{}`.", name))
+  }
+
+  interaction.create_followup_message(
+    &context.http,
+    |followup: &mut CreateInteractionResponseFollowup<'_>| {
+      followup
+        .ephemeral(true)
+        .add_embed(embed)
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub async fn autocomplete(
+  context: &Context,
+  interaction: &AutocompleteInteraction
+) -> Result<()> {
+  let query: String = interaction.data.options
+    .iter()
+    .flat_map(|option: &CommandDataOption| &option.options)
+    .find(|option: &&CommandDataOption| option.focused)
+    .and_then(|option: &CommandDataOption| option.value.as_ref()?.as_str())
+    .unwrap_or("")
+    .trim_start_matches('@')
+    .to_lowercase();
+
+  let accounts: StoredAccounts = AccessTokenCache::get()
+    .request(*interaction.user.id.as_u64())
+    .await?
+    .map(|cache_data: CacheData| cache_data.accounts)
+    .unwrap_or_default();
+
+  interaction.create_autocomplete_response(
+    &context.http,
+    |response: &mut CreateAutocompleteResponse| {
+      for token in accounts.tokens
+        .iter()
+        .filter(|token: &&StoredToken| token.is_identified())
+        .filter(|token: &&StoredToken| token.screen_name.to_lowercase().contains(&query))
+        .take(MAX_AUTOCOMPLETE_CHOICES)
+      {
+        response.add_string_choice(format!("@{}", token.screen_name), &token.account_id);
+      }
+
+      response
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub fn register(
+  command: &mut CreateApplicationCommand
+) -> &mut CreateApplicationCommand {
+  command
+    .name("accounts")
+    .name_localized("zh-TW", "帳號")
+    .name_localized("zh-CN", "账号")
+    .description("Manage your connected Twitter accounts")
+    .description_localized("zh-TW", "管理已連接的 Twitter 帳號")
+    .description_localized("zh-CN", "管理已连接的 Twitter 账号")
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("list")
+          .name_localized("zh-TW", "列表")
+          .name_localized("zh-CN", "列表")
+          .description("List your connected Twitter accounts")
+          .description_localized("zh-TW", "列出已連接的 Twitter 帳號")
+          .description_localized("zh-CN", "列出已连接的 Twitter 账号")
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("use")
+          .name_localized("zh-TW", "使用")
+          .name_localized("zh-CN", "使用")
+          .description("Choose which account reactions and buttons act as")
+          .description_localized("zh-TW", "選擇表情符號反應與按鈕所使用的帳號")
+          .description_localized("zh-CN", "选择表情符号回应与按钮所使用的账号")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::String)
+                .name("account")
+                .name_localized("zh-TW", "帳號")
+                .name_localized("zh-CN", "账号")
+                .description("Twitter username")
+                .description_localized("zh-TW", "Twitter 使用者名稱")
+                .description_localized("zh-CN", "Twitter 用户名")
+                .required(true)
+                .set_autocomplete(true)
+            }
+          )
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("server")
+                .name_localized("zh-TW", "伺服器")
+                .name_localized("zh-CN", "服务器")
+                .description("Only use this account in this server")
+                .description_localized("zh-TW", "僅在此伺服器使用此帳號")
+                .description_localized("zh-CN", "仅在此服务器使用此账号")
+            }
+          )
+      }
+    )
+}
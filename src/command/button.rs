@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use rust_i18n::t;
+use serenity::{
+  model::prelude::{
+    interaction::{
+      message_component::MessageComponentInteraction,
+      InteractionResponseType
+    },
+    component::ButtonStyle,
+    Message,
+    ReactionType
+  },
+  prelude::Context,
+  builder::{
+    CreateMessage,
+    EditMessage,
+    CreateComponents,
+    CreateActionRow,
+    CreateButton,
+    CreateAllowedMentions,
+    CreateInteractionResponse,
+    CreateInteractionResponseData,
+    CreateInteractionResponseFollowup,
+    CreateEmbed
+  },
+  utils::Color
+};
+use tracing::log::info;
+use anyhow::{Result, anyhow};
+
+use crate::core::{
+  utils::{EMBED_INFO_COLOR, EMBED_ERROR_COLOR, get_parent_channel_id},
+  oauth::TwitterClient,
+  model::get_tweet_url,
+  settings::{GuildSettingsStore, GuildSettings},
+  action::{TweetAction, ToggleResult},
+  error::TwitterError
+};
+
+
+static MAX_BUTTON_ROWS: usize = 5;
+
+
+fn parse_custom_id(custom_id: &str) -> Option<(TweetAction, Arc<str>)> {
+  let (action, tweet_id): (&str, &str) = custom_id
+    .strip_prefix("action:")?
+    .split_once(":")?;
+
+  Some((TweetAction::from_name(action)?, tweet_id.into()))
+}
+
+pub fn build_action_rows<'a>(
+  components: &'a mut CreateComponents,
+  tweet_ids: &[Arc<str>],
+  actions: &[TweetAction]
+) -> &'a mut CreateComponents {
+  for (index, tweet_id) in tweet_ids.iter().take(MAX_BUTTON_ROWS).enumerate() {
+    components.create_action_row(
+      |row: &mut CreateActionRow| {
+        if tweet_ids.len() > 1 {
+          row.create_button(
+            |button: &mut CreateButton| {
+              button
+                .style(ButtonStyle::Link)
+                .label(format!("#{}", index + 1))
+                .url(get_tweet_url(tweet_id))
+            }
+          );
+        }
+
+        for action in actions {
+          row.create_button(
+            |button: &mut CreateButton| {
+              button
+                .style(ButtonStyle::Secondary)
+                .emoji(ReactionType::Unicode(action.emoji().to_string()))
+                .custom_id(format!("action:{}:{tweet_id}", action.name()))
+            }
+          );
+        }
+
+        row
+      }
+    );
+  }
+
+  components
+}
+
+pub async fn send_buttons(
+  context: &Context,
+  message: &Message,
+  tweet_ids: &[Arc<str>],
+  actions: &[TweetAction]
+) -> Result<Message> {
+  Ok(
+    message.channel_id.send_message(
+      &context.http,
+      |reply: &mut CreateMessage<'_>| {
+        reply
+          .reference_message(message)
+          .allowed_mentions(
+            |mentions: &mut CreateAllowedMentions| {
+              mentions.replied_user(false)
+            }
+          )
+          .components(
+            |components: &mut CreateComponents| {
+              build_action_rows(components, tweet_ids, actions)
+            }
+          )
+      }
+    ).await?
+  )
+}
+
+pub fn find_buttons(replies: &[Message]) -> Option<Message> {
+  replies
+    .iter()
+    .find(|reply: &&Message| reply.embeds.is_empty() && !reply.components.is_empty())
+    .cloned()
+}
+
+pub async fn edit_buttons(
+  context: &Context,
+  buttons: &mut Message,
+  tweet_ids: &[Arc<str>],
+  actions: &[TweetAction]
+) -> Result<()> {
+  buttons.edit(
+    &context.http,
+    |message: &mut EditMessage<'_>| {
+      message.components(
+        |components: &mut CreateComponents| {
+          build_action_rows(components, tweet_ids, actions)
+        }
+      )
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub async fn execute(
+  context: &Context,
+  interaction: &MessageComponentInteraction
+) -> Result<()> {
+  let (action, tweet_id): (TweetAction, Arc<str>) = parse_custom_id(
+    &interaction.data.custom_id
+  ).ok_or(anyhow!("Unknown component `{}`.", interaction.data.custom_id))?;
+
+  let settings: GuildSettings = GuildSettingsStore::get().resolve(interaction.guild_id).await;
+
+  if !settings.is_action_enabled(action) || !settings.is_channel_allowed(get_parent_channel_id(context, interaction.channel_id).await) {
+    interaction.create_interaction_response(
+      &context.http,
+      |response: &mut CreateInteractionResponse<'_>| {
+        response
+          .kind(InteractionResponseType::ChannelMessageWithSource)
+          .interaction_response_data(
+            |message: &mut CreateInteractionResponseData<'_>| {
+              message
+                .ephemeral(true)
+                .embed(
+                  |embed: &mut CreateEmbed| {
+                    embed
+                      .color(Color::new(EMBED_ERROR_COLOR))
+                      .title(
+                        t!(
+                          "command.button.disabled-embed.title",
+                          locale = &interaction.locale
+                        )
+                      )
+                      .description(
+                        t!(
+                          "command.button.disabled-embed.description",
+                          locale = &interaction.locale
+                        )
+                      )
+                  }
+                )
+            }
+          )
+      }
+    ).await?;
+
+    return Ok(());
+  }
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      response
+        .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        .interaction_response_data(
+          |message: &mut CreateInteractionResponseData<'_>| {
+            message.ephemeral(true)
+          }
+        )
+    }
+  ).await?;
+
+  let result: Result<ToggleResult> = match TwitterClient::find_client(context, &interaction.user, interaction.guild_id).await {
+    Ok(mut twitter_client) => action.toggle(
+      &mut twitter_client,
+      *interaction.user.id.as_u64(),
+      &tweet_id
+    ).await,
+    Err(why) => Err(why)
+  };
+
+  info!(
+    "Toggle action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id} | Result: {result:?}",
+    action = action.emoji(),
+    result = result.as_ref().ok(),
+    tweet_id = tweet_id,
+    user_id = interaction.user.id.as_u64(),
+    guild_id = match interaction.guild_id {
+      Some(guild_id) => *guild_id.as_u64(),
+      None => 0
+    },
+  );
+
+  if let Err(why) = &result {
+    action.record_failure(*interaction.user.id.as_u64(), &tweet_id, why);
+  }
+
+  interaction.create_followup_message(
+    &context.http,
+    |followup: &mut CreateInteractionResponseFollowup<'_>| {
+      followup
+        .ephemeral(true)
+        .embed(
+          |embed: &mut CreateEmbed| {
+            match &result {
+              Ok(toggle_result) => embed
+                .color(Color::new(EMBED_INFO_COLOR))
+                .description(
+                  t!(
+                    &format!(
+                      "command.button.{action}.{state}",
+                      action = action.name(),
+                      state = match toggle_result {
+                        ToggleResult::Invoked(_) => "invoked",
+                        ToggleResult::Revoked(_) => "revoked"
+                      }
+                    ),
+                    locale = &interaction.locale
+                  )
+                ),
+              Err(why) => TwitterError::classify(why).build_embed(embed, &interaction.locale)
+            }
+          }
+        )
+    }
+  ).await?;
+
+  result.map(|_| ())
+}
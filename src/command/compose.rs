@@ -0,0 +1,406 @@
+use std::sync::Arc;
+
+use rust_i18n::t;
+use serenity::{
+  model::{
+    prelude::{
+      interaction::{
+        application_command::{ApplicationCommandInteraction, ResolvedTarget},
+        message_component::MessageComponentInteraction,
+        modal::ModalSubmitInteraction,
+        InteractionResponseType
+      },
+      command::CommandType,
+      component::{ActionRowComponent, InputTextStyle},
+      GuildId
+    },
+    user::User
+  },
+  prelude::Context,
+  builder::{
+    CreateApplicationCommand,
+    CreateInteractionResponse,
+    CreateInteractionResponseData,
+    CreateInteractionResponseFollowup,
+    CreateComponents,
+    CreateActionRow,
+    CreateInputText,
+    CreateSelectMenu,
+    CreateSelectMenuOptions,
+    CreateSelectMenuOption,
+    CreateEmbed
+  },
+  utils::Color
+};
+use tracing::log::info;
+use anyhow::{Result, anyhow};
+
+use crate::core::{
+  utils::{EMBED_INFO_COLOR, EMBED_ERROR_COLOR, collect_tweet_ids, get_parent_channel_id},
+  settings::{GuildSettingsStore, GuildSettings},
+  oauth::TwitterClient,
+  model::{Tweet, get_tweet_url},
+  error::TwitterError
+};
+
+
+static TWEET_MAX_LENGTH: u64 = 280;
+
+static MAX_SELECT_OPTIONS: usize = 25;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComposeAction {
+  Reply,
+  Quote
+}
+
+impl ComposeAction {
+  fn from_custom_id(custom_id: &str) -> Option<(ComposeAction, Arc<str>)> {
+    let (action, tweet_id): (&str, &str) = custom_id.split_once(":")?;
+
+    match action {
+      "compose-reply" => Some((ComposeAction::Reply, tweet_id.into())),
+      "compose-quote" => Some((ComposeAction::Quote, tweet_id.into())),
+      _ => None
+    }
+  }
+
+  fn custom_id(self: &Self, tweet_id: &str) -> String {
+    match self {
+      ComposeAction::Reply => format!("compose-reply:{tweet_id}"),
+      ComposeAction::Quote => format!("compose-quote:{tweet_id}")
+    }
+  }
+
+  fn from_select_custom_id(custom_id: &str) -> Option<ComposeAction> {
+    match custom_id.strip_prefix("compose-select:")? {
+      "reply" => Some(ComposeAction::Reply),
+      "quote" => Some(ComposeAction::Quote),
+      _ => None
+    }
+  }
+
+  fn select_custom_id(self: &Self) -> String {
+    format!("compose-select:{}", self.name())
+  }
+
+  fn name(self: &Self) -> &'static str {
+    match self {
+      ComposeAction::Reply => "reply",
+      ComposeAction::Quote => "quote"
+    }
+  }
+}
+
+
+pub async fn execute(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction,
+  action: ComposeAction
+) -> Result<()> {
+  let settings: GuildSettings = GuildSettingsStore::get().resolve(interaction.guild_id).await;
+  let channel_allowed: bool = settings.is_channel_allowed(get_parent_channel_id(context, interaction.channel_id).await);
+
+  let tweet_ids: Vec<Arc<str>> = match (channel_allowed, interaction.data.target()) {
+    (true, Some(ResolvedTarget::Message(message))) => collect_tweet_ids(context, &message, settings.link_scope()).await,
+    _ => Vec::new()
+  };
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      match tweet_ids.as_slice() {
+        [] => response
+          .kind(InteractionResponseType::ChannelMessageWithSource)
+          .interaction_response_data(
+            |message: &mut CreateInteractionResponseData<'_>| {
+              message
+                .ephemeral(true)
+                .embed(
+                  |embed: &mut CreateEmbed| {
+                    embed
+                      .color(Color::new(EMBED_ERROR_COLOR))
+                      .title(
+                        t!(
+                          "command.compose.no-tweet-embed.title",
+                          locale = &interaction.locale
+                        )
+                      )
+                      .description(
+                        t!(
+                          "command.compose.no-tweet-embed.description",
+                          locale = &interaction.locale
+                        )
+                      )
+                  }
+                )
+            }
+          ),
+        [tweet_id] => build_modal(response, action, tweet_id, &interaction.locale),
+        tweet_ids => response
+          .kind(InteractionResponseType::ChannelMessageWithSource)
+          .interaction_response_data(
+            |message: &mut CreateInteractionResponseData<'_>| {
+              message
+                .ephemeral(true)
+                .embed(
+                  |embed: &mut CreateEmbed| {
+                    embed
+                      .color(Color::new(EMBED_INFO_COLOR))
+                      .title(
+                        t!(
+                          "command.compose.select-embed.title",
+                          locale = &interaction.locale
+                        )
+                      )
+                      .description(
+                        t!(
+                          "command.compose.select-embed.description",
+                          locale = &interaction.locale
+                        )
+                      )
+                  }
+                )
+                .components(
+                  |components: &mut CreateComponents| {
+                    components.create_action_row(
+                      |row: &mut CreateActionRow| {
+                        row.create_select_menu(
+                          |menu: &mut CreateSelectMenu| {
+                            menu
+                              .custom_id(action.select_custom_id())
+                              .placeholder(
+                                t!(
+                                  "command.compose.select-embed.placeholder",
+                                  locale = &interaction.locale
+                                )
+                              )
+                              .min_values(1)
+                              .max_values(1)
+                              .options(
+                                |options: &mut CreateSelectMenuOptions| {
+                                  for (index, tweet_id) in tweet_ids.iter().take(MAX_SELECT_OPTIONS).enumerate() {
+                                    options.create_option(
+                                      |option: &mut CreateSelectMenuOption| {
+                                        option
+                                          .label(
+                                            t!(
+                                              "command.compose.select-embed.option",
+                                              locale = &interaction.locale,
+                                              index = index + 1
+                                            )
+                                          )
+                                          .value(tweet_id)
+                                          .description(get_tweet_url(tweet_id))
+                                      }
+                                    );
+                                  }
+
+                                  options
+                                }
+                              )
+                          }
+                        )
+                      }
+                    )
+                  }
+                )
+            }
+          )
+      }
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub async fn select(
+  context: &Context,
+  interaction: &MessageComponentInteraction
+) -> Result<()> {
+  let action: ComposeAction = ComposeAction::from_select_custom_id(
+    &interaction.data.custom_id
+  ).ok_or(anyhow!("Unknown component `{}`.", interaction.data.custom_id))?;
+
+  let tweet_id: &str = interaction.data.values
+    .first()
+    .ok_or(anyhow!("Get selected tweet failed."))?;
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      build_modal(response, action, tweet_id, &interaction.locale)
+    }
+  ).await?;
+
+  Ok(())
+}
+
+fn build_modal<'a, 'b>(
+  response: &'a mut CreateInteractionResponse<'b>,
+  action: ComposeAction,
+  tweet_id: &str,
+  locale: &str
+) -> &'a mut CreateInteractionResponse<'b> {
+  response
+    .kind(InteractionResponseType::Modal)
+    .interaction_response_data(
+      |modal: &mut CreateInteractionResponseData<'_>| {
+        modal
+          .custom_id(action.custom_id(tweet_id))
+          .title(
+            t!(
+              &format!("command.compose.{}-modal.title", action.name()),
+              locale = locale
+            )
+          )
+          .components(
+            |components: &mut CreateComponents| {
+              components.create_action_row(
+                |row: &mut CreateActionRow| {
+                  row.create_input_text(
+                    |input: &mut CreateInputText| {
+                      input
+                        .custom_id("text")
+                        .style(InputTextStyle::Paragraph)
+                        .label(
+                          t!(
+                            "command.compose.text-input.label",
+                            locale = locale
+                          )
+                        )
+                        .placeholder(
+                          t!(
+                            "command.compose.text-input.placeholder",
+                            locale = locale
+                          )
+                        )
+                        .min_length(1)
+                        .max_length(TWEET_MAX_LENGTH)
+                        .required(true)
+                    }
+                  )
+                }
+              )
+            }
+          )
+      }
+    )
+}
+
+pub async fn submit(
+  context: &Context,
+  interaction: &ModalSubmitInteraction
+) -> Result<()> {
+  let (action, tweet_id): (ComposeAction, Arc<str>) = ComposeAction::from_custom_id(
+    &interaction.data.custom_id
+  ).ok_or(anyhow!("Unknown modal `{}`.", interaction.data.custom_id))?;
+
+  let text: Arc<str> = interaction.data.components
+    .iter()
+    .flat_map(|row| row.components.iter())
+    .find_map(
+      |component: &ActionRowComponent| match component {
+        ActionRowComponent::InputText(input) if input.custom_id == "text" => Some(input.value.as_str()),
+        _ => None
+      }
+    )
+    .ok_or(anyhow!("Get tweet text failed."))?
+    .into();
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      response
+        .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        .interaction_response_data(
+          |message: &mut CreateInteractionResponseData<'_>| {
+            message.ephemeral(true)
+          }
+        )
+    }
+  ).await?;
+
+  let result: Result<Tweet> = post_tweet(context, &interaction.user, interaction.guild_id, action, &tweet_id, &text).await;
+
+  info!(
+    "Compose action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id} | Applied: {applied}",
+    action = action.name(),
+    applied = result.is_ok(),
+    tweet_id = tweet_id,
+    user_id = interaction.user.id.as_u64(),
+    guild_id = match interaction.guild_id {
+      Some(guild_id) => *guild_id.as_u64(),
+      None => 0
+    },
+  );
+
+  interaction.create_followup_message(
+    &context.http,
+    |followup: &mut CreateInteractionResponseFollowup<'_>| {
+      followup
+        .ephemeral(true)
+        .embed(
+          |embed: &mut CreateEmbed| {
+            match &result {
+              Ok(tweet) => embed
+                .color(Color::new(EMBED_INFO_COLOR))
+                .title(
+                  t!(
+                    &format!("command.compose.{}-success-embed.title", action.name()),
+                    locale = &interaction.locale
+                  )
+                )
+                .description(
+                  t!(
+                    "command.compose.success-embed.description",
+                    locale = &interaction.locale,
+                    tweet_url = tweet.url()
+                  )
+                ),
+              Err(why) => TwitterError::classify(why).build_embed(embed, &interaction.locale)
+            }
+          }
+        )
+    }
+  ).await?;
+
+  result.map(|_| ())
+}
+
+async fn post_tweet(
+  context: &Context,
+  user: &User,
+  guild_id: Option<GuildId>,
+  action: ComposeAction,
+  tweet_id: &str,
+  text: &str
+) -> Result<Tweet> {
+  let mut twitter_client: TwitterClient = TwitterClient::find_client(context, user, guild_id).await?;
+
+  match action {
+    ComposeAction::Reply => twitter_client.reply(tweet_id, text).await,
+    ComposeAction::Quote => twitter_client.quote(tweet_id, text).await
+  }
+}
+
+pub fn register_reply(
+  command: &mut CreateApplicationCommand
+) -> &mut CreateApplicationCommand {
+  command
+    .name("Reply on Twitter")
+    .name_localized("zh-TW", "在 Twitter 上回覆")
+    .name_localized("zh-CN", "在 Twitter 上回复")
+    .kind(CommandType::Message)
+}
+
+pub fn register_quote(
+  command: &mut CreateApplicationCommand
+) -> &mut CreateApplicationCommand {
+  command
+    .name("Quote on Twitter")
+    .name_localized("zh-TW", "在 Twitter 上引用")
+    .name_localized("zh-CN", "在 Twitter 上引用")
+    .kind(CommandType::Message)
+}
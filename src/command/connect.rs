@@ -1,6 +1,6 @@
 use std::{sync::{Arc, OnceLock}, time::Duration};
 
-use regex::Regex;
+use regex::{Regex, Captures, Match};
 use rust_i18n::t;
 use serenity::{
   model::prelude::{
@@ -18,10 +18,15 @@ use serenity::{
   },
   utils::Color
 };
-use anyhow::Result;
+use tracing::log::warn;
+use anyhow::{Result, anyhow};
 
 use crate::core::{
   oauth::TwitterClient,
+  callback::{PendingCallback, get_callback_url},
+  oauth2::{OAuth2Client, AuthorizationRequest, AuthFlow, get_auth_flow},
+  store::{Credential, StoredToken},
+  model::TwitterUser,
   utils::{
     EMBED_INFO_COLOR,
     EMBED_ERROR_COLOR,
@@ -33,6 +38,9 @@ use crate::core::{
 
 
 static AUTH_PIN_REGEX: OnceLock<Regex> = OnceLock::new();
+static AUTH_CODE_REGEX: OnceLock<Regex> = OnceLock::new();
+static AUTH_STATE_REGEX: OnceLock<Regex> = OnceLock::new();
+static AUTH_TIMEOUT: Duration = Duration::from_secs(75);
 
 
 pub async fn execute(
@@ -65,42 +73,23 @@ pub fn register(
 
 fn build_auth_embed(
   embed: &mut CreateEmbed,
-  auth_link: Arc<str>,
-  locale: Arc<str>
+  title: Arc<str>,
+  description: Arc<str>
 ) -> &mut CreateEmbed {
   embed
     .color(Color::new(EMBED_INFO_COLOR))
-    .title(
-      t!(
-        "command.connect.auth-embed.title",
-        locale = &locale
-      )
-    )
-    .description(
-      t!(
-        "command.connect.auth-embed.description",
-        locale = &locale,
-        auth_link = auth_link
-      )
-    )
+    .title(title)
+    .description(description)
 }
 
-async fn connect_account(
+async fn send_auth_embed(
   context: &Context,
   interaction: &ApplicationCommandInteraction,
-  is_dm: bool
+  dm_channel: &PrivateChannel,
+  is_dm: bool,
+  title: Arc<str>,
+  description: Arc<str>
 ) -> Result<()> {
-  let dm_channel: PrivateChannel = interaction
-    .user
-    .create_dm_channel(&context.http)
-    .await?;
-
-  clean_up_dm(context, &dm_channel).await?;
-
-  let mut twitter_client: TwitterClient = TwitterClient::new(None, None)?;
-
-  let auth_link: Arc<str> = twitter_client.get_authorization_url().await?;
-
   if is_dm {
     interaction.create_followup_message(
       &context.http,
@@ -110,8 +99,8 @@ async fn connect_account(
             |embed: &mut CreateEmbed| {
               build_auth_embed(
                 embed,
-                auth_link,
-                interaction.locale.clone().into()
+                title,
+                description
               )
             }
           )
@@ -124,35 +113,254 @@ async fn connect_account(
         |embed: &mut CreateEmbed| {
           build_auth_embed(
             embed,
-            auth_link,
-            interaction.locale.clone().into()
+            title,
+            description
           )
         }
       )
     ).await?;
   }
 
-  let pin_code: Option<Arc<Message>> = interaction.user
+  Ok(())
+}
+
+async fn await_auth_reply(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction,
+  dm_channel: &PrivateChannel,
+  regex: &'static Regex
+) -> Option<Arc<Message>> {
+  interaction.user
     .await_reply(context)
     .author_id(interaction.user.id)
     .channel_id(dm_channel.id)
-    .timeout(Duration::from_secs(75))
+    .timeout(AUTH_TIMEOUT)
     .filter(
       |message: &Arc<Message>| {
-        AUTH_PIN_REGEX
-          .get_or_init(
-            || {
-              Regex::new(
-                "[0-9]{7}"
-              ).expect("Regex init failed.")
-            }
-          )
-          .is_match(&message.content)
+        regex.is_match(&message.content)
       }
     )
-    .await;
+    .await
+}
+
+fn get_auth_code_regex() -> &'static Regex {
+  AUTH_CODE_REGEX.get_or_init(
+    || {
+      Regex::new(
+        r"(?:^|[?&\s])code=(?P<code>[^&\s]+)"
+      ).expect("Regex init failed.")
+    }
+  )
+}
+
+fn parse_authorization_code(content: &str, state: &str) -> Option<Arc<str>> {
+  let captures: Captures = get_auth_code_regex().captures(content)?;
+
+  let returned_state: &str = AUTH_STATE_REGEX
+    .get_or_init(
+      || {
+        Regex::new(
+          r"[?&]state=(?P<state>[^&\s]+)"
+        ).expect("Regex init failed.")
+      }
+    )
+    .captures(content)
+    .and_then(|captures: Captures| captures.name("state"))
+    .map(|state: Match| state.as_str())?;
+
+  if returned_state != state {
+    return None;
+  }
+
+  Some(captures.name("code")?.as_str().into())
+}
+
+async fn connect_oauth1(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction,
+  dm_channel: &PrivateChannel,
+  is_dm: bool
+) -> Result<Option<Result<Credential>>> {
+  let mut twitter_client: TwitterClient = TwitterClient::new(None, None)?;
+
+  let auth_link: Arc<str> = twitter_client.get_authorization_url().await?;
+
+  let pending_callback: Option<PendingCallback> = match get_callback_url() {
+    Some(_) => Some(
+      PendingCallback::register(
+        twitter_client
+          .get_request_token()
+          .ok_or(anyhow!("Get request token failed."))?
+      )
+    ),
+    None => None
+  };
+
+  send_auth_embed(
+    context,
+    interaction,
+    dm_channel,
+    is_dm,
+    t!(
+      "command.connect.auth-embed.title",
+      locale = &interaction.locale
+    ).into(),
+    match pending_callback {
+      Some(_) => t!(
+        "command.connect.callback-auth-embed.description",
+        locale = &interaction.locale,
+        auth_link = auth_link
+      ),
+      None => t!(
+        "command.connect.auth-embed.description",
+        locale = &interaction.locale,
+        auth_link = auth_link
+      )
+    }.into()
+  ).await?;
+
+  let verifier: Arc<str> = match pending_callback {
+    Some(pending_callback) => match pending_callback.wait(AUTH_TIMEOUT).await {
+      Some(Ok(verifier)) => verifier,
+      Some(Err(why)) => return Ok(Some(Err(why))),
+      None => return Ok(None)
+    },
+    None => match await_auth_reply(
+      context,
+      interaction,
+      dm_channel,
+      AUTH_PIN_REGEX.get_or_init(
+        || {
+          Regex::new(
+            "[0-9]{7}"
+          ).expect("Regex init failed.")
+        }
+      )
+    ).await {
+      Some(pin_code) => pin_code.content.as_str().into(),
+      None => return Ok(None)
+    }
+  };
+
+  Ok(
+    Some(
+      twitter_client
+        .get_access_token(&verifier)
+        .await
+        .map(
+          |(access_token, access_token_secret): (&str, &str)| Credential::OAuth1 {
+            access_token: access_token.into(),
+            access_token_secret: access_token_secret.into()
+          }
+        )
+    )
+  )
+}
+
+async fn connect_oauth2(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction,
+  dm_channel: &PrivateChannel,
+  is_dm: bool
+) -> Result<Option<Result<Credential>>> {
+  let mut oauth2_client: OAuth2Client = OAuth2Client::new()?;
+
+  let authorization_request: AuthorizationRequest = oauth2_client.authorization_request();
 
-  if pin_code.is_none() {
+  let pending_callback: Option<PendingCallback> = match get_callback_url() {
+    Some(_) => Some(PendingCallback::register(authorization_request.state.clone())),
+    None => None
+  };
+
+  send_auth_embed(
+    context,
+    interaction,
+    dm_channel,
+    is_dm,
+    t!(
+      "command.connect.oauth2-auth-embed.title",
+      locale = &interaction.locale
+    ).into(),
+    match pending_callback {
+      Some(_) => t!(
+        "command.connect.callback-auth-embed.description",
+        locale = &interaction.locale,
+        auth_link = authorization_request.url
+      ),
+      None => t!(
+        "command.connect.oauth2-auth-embed.description",
+        locale = &interaction.locale,
+        auth_link = authorization_request.url
+      )
+    }.into()
+  ).await?;
+
+  if let Some(pending_callback) = pending_callback {
+    return match pending_callback.wait(AUTH_TIMEOUT).await {
+      Some(Ok(code)) => Ok(
+        Some(
+          oauth2_client.exchange_code(
+            &code,
+            &authorization_request.code_verifier
+          ).await
+        )
+      ),
+      Some(Err(why)) => Ok(Some(Err(why))),
+      None => Ok(None)
+    };
+  }
+
+  let redirect: Arc<Message> = match await_auth_reply(
+    context,
+    interaction,
+    dm_channel,
+    get_auth_code_regex()
+  ).await {
+    Some(redirect) => redirect,
+    None => return Ok(None)
+  };
+
+  match parse_authorization_code(&redirect.content, &authorization_request.state) {
+    Some(code) => Ok(
+      Some(
+        oauth2_client.exchange_code(
+          &code,
+          &authorization_request.code_verifier
+        ).await
+      )
+    ),
+    None => Ok(Some(Err(anyhow!("Authorization state mismatch."))))
+  }
+}
+
+async fn connect_account(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction,
+  is_dm: bool
+) -> Result<()> {
+  let dm_channel: PrivateChannel = interaction
+    .user
+    .create_dm_channel(&context.http)
+    .await?;
+
+  clean_up_dm(context, &dm_channel).await?;
+
+  let credential: Option<Result<Credential>> = match get_auth_flow() {
+    AuthFlow::OAuth1 => connect_oauth1(
+      context,
+      interaction,
+      &dm_channel,
+      is_dm
+    ).await?,
+    AuthFlow::OAuth2 => connect_oauth2(
+      context,
+      interaction,
+      &dm_channel,
+      is_dm
+    ).await?
+  };
+
+  if credential.is_none() {
     dm_channel.send_message(
       &context.http,
       |message: &mut CreateMessage<'_>| message.add_embed(
@@ -183,17 +391,28 @@ async fn connect_account(
     return Ok(());
   }
 
-  let access_token_pair: Result<(&str, &str)> = twitter_client
-    .get_access_token(&pin_code.unwrap().content)
-    .await;
+  if let Ok(credential) = credential.unwrap() {
+    let account: Option<TwitterUser> = match TwitterClient::from_credential(&credential)?.get_me().await {
+      Ok(account) => Some(account),
+      Err(why) => {
+        warn!("Resolve connected account failed: {:?} | User: {}", why, interaction.user.id);
+        None
+      }
+    };
 
-  if let Ok((access_token, access_token_secret)) = access_token_pair {
     AccessTokenCache::get().add(
       *interaction.user.id.as_u64(),
-      access_token,
-      access_token_secret
-    ).await;
-    
+      match &account {
+        Some(account) => StoredToken::new(
+          credential,
+          account.id.clone(),
+          account.username.clone(),
+          interaction.locale.as_str().into()
+        ),
+        None => StoredToken::unidentified(credential, interaction.locale.as_str().into())
+      }
+    ).await?;
+
     dm_channel.send_message(
       &context.http,
       |message: &mut CreateMessage<'_>| message.add_embed(
@@ -211,22 +430,24 @@ async fn connect_account(
                 "command.connect.success-embed.description",
                 locale = &interaction.locale
               )
-            )
+            );
+
+          if let Some(account) = &account {
+            embed.field(
+              t!(
+                "command.connect.success-embed.account",
+                locale = &interaction.locale
+              ),
+              format!("[@{username}]({url})", username = account.username, url = account.url()),
+              false
+            );
+          }
+
+          embed
         }
       )
     ).await?;
 
-    dm_channel.send_message(
-      &context.http,
-      |message: &mut CreateMessage<'_>| message.content(
-        format!(
-          "Twitter User Access Token\n||`{access_token}`||\n||`{access_token_secret}`||",
-          access_token = access_token,
-          access_token_secret = access_token_secret
-        )
-      )
-    ).await?.pin(&context.http).await?;
-
     return Ok(());
   } else {
     dm_channel.send_message(
@@ -15,6 +15,7 @@ use serenity::{
   },
   utils::Color
 };
+use tracing::log::{info, warn};
 use anyhow::Result;
 
 use crate::core::{
@@ -23,10 +24,17 @@ use crate::core::{
     clean_up_dm,
     check_dm
   },
-  cache::AccessTokenCache
+  oauth::TwitterClient,
+  api::forget_user_id,
+  cache::{AccessTokenCache, CacheData},
+  store::{StoredAccounts, StoredToken},
+  error::TwitterError
 };
 
 
+type Revocation = (StoredToken, Result<(), TwitterError>);
+
+
 pub async fn execute(
   context: &Context,
   interaction: &ApplicationCommandInteraction
@@ -55,10 +63,11 @@ pub fn register(
     .description_localized("zh-CN", "中断与 Twitter 帐号的连接")
 }
 
-fn build_success_embed(
-  embed: &mut CreateEmbed,
-  locale: Arc<str>
-) -> &mut CreateEmbed {
+fn build_success_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  locale: Arc<str>,
+  revocations: &[Revocation]
+) -> &'a mut CreateEmbed {
   embed
     .color(Color::new(EMBED_INFO_COLOR))
     .title(
@@ -72,7 +81,51 @@ fn build_success_embed(
         "command.disconnect.success-embed.description",
         locale = &locale
       )
-    )
+    );
+
+  for (token, result) in revocations {
+    embed.field(
+      match token.is_identified() {
+        true => format!("@{}", token.screen_name),
+        false => t!("command.disconnect.success-embed.unknown", locale = &locale)
+      },
+      match result {
+        Ok(()) => t!("command.disconnect.success-embed.revoked", locale = &locale),
+        Err(error) => t!(
+          "command.disconnect.success-embed.revoke-failed",
+          locale = &locale,
+          code = error.code()
+        )
+      },
+      false
+    );
+  }
+
+  embed
+}
+
+async fn revoke_token(user_id: u64, token: &StoredToken) -> Result<(), TwitterError> {
+  forget_user_id(token.credential.access_token()).await;
+
+  let result: Result<(), TwitterError> = match TwitterClient::revoke_credential(&token.credential).await {
+    Ok(()) => Ok(()),
+    Err(why) => match TwitterError::classify(&why) {
+      TwitterError::Unauthorized | TwitterError::Revoked => Ok(()),
+      error => {
+        warn!("Revoke credential failed: {:?} | User: {}", why, user_id);
+        Err(error)
+      }
+    }
+  };
+
+  info!(
+    "Revoke credential | User: {user_id} | Account: {account_id} | Revoked: {revoked}",
+    user_id = user_id,
+    account_id = token.account_id,
+    revoked = result.is_ok()
+  );
+
+  result
 }
 
 async fn disconnect_account(
@@ -87,9 +140,22 @@ async fn disconnect_account(
 
   clean_up_dm(context, &dm_channel).await?;
 
-  AccessTokenCache::get().purge(
-    *interaction.user.id.as_u64()
-  ).await;
+  let user_id: u64 = *interaction.user.id.as_u64();
+
+  let accounts: StoredAccounts = AccessTokenCache::get()
+    .request(user_id)
+    .await?
+    .map(|cache_data: CacheData| cache_data.accounts)
+    .unwrap_or_default();
+
+  let mut revocations: Vec<Revocation> = Vec::with_capacity(accounts.tokens.len());
+
+  for token in accounts.tokens {
+    let result: Result<(), TwitterError> = revoke_token(user_id, &token).await;
+    revocations.push((token, result));
+  }
+
+  AccessTokenCache::get().purge(user_id).await?;
 
   if is_dm {
     interaction.create_followup_message(
@@ -100,7 +166,8 @@ async fn disconnect_account(
             |embed: &mut CreateEmbed| {
               build_success_embed(
                 embed,
-                interaction.locale.clone().into()
+                interaction.locale.clone().into(),
+                &revocations
               )
             }
           )
@@ -113,7 +180,8 @@ async fn disconnect_account(
         |embed: &mut CreateEmbed| {
           build_success_embed(
             embed,
-            interaction.locale.clone().into()
+            interaction.locale.clone().into(),
+            &revocations
           )
         }
       )
@@ -1,4 +1,10 @@
 pub mod connect;
 pub mod disconnect;
+pub mod accounts;
+pub mod status;
 pub mod support;
 pub mod invite;
+pub mod compose;
+pub mod settings;
+pub mod button;
+pub mod preview;
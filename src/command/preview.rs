@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use serenity::{
+  model::prelude::{
+    Message,
+    ReactionType
+  },
+  prelude::Context,
+  builder::{
+    CreateMessage,
+    CreateComponents,
+    CreateAllowedMentions,
+    CreateEmbed
+  }
+};
+use tracing::log::{info, error};
+use anyhow::Result;
+
+use crate::core::{
+  oauth::TwitterClient,
+  model::TweetPreview,
+  embed::build_tweet_embeds,
+  settings::ActionMode,
+  action::TweetAction,
+  link::parse_tweet_id,
+  utils::get_guild_locale
+};
+
+use super::button::build_action_rows;
+
+
+pub static MAX_PREVIEWS: usize = 3;
+
+
+pub fn get_previewed_ids(replies: &[Message]) -> Vec<Arc<str>> {
+  replies
+    .iter()
+    .flat_map(|reply: &Message| reply.embeds.first())
+    .filter_map(|embed| embed.url.as_deref().and_then(parse_tweet_id))
+    .collect()
+}
+
+pub async fn apply_previews(
+  context: &Context,
+  message: &Message,
+  tweet_ids: Vec<Arc<str>>,
+  previewed: usize,
+  mode: ActionMode,
+  actions: &[TweetAction]
+) -> Vec<Arc<str>> {
+  let locale: String = get_guild_locale(context, message.guild_id).await;
+  let mut previewed: usize = previewed;
+  let mut unpreviewed_ids: Vec<Arc<str>> = Vec::new();
+
+  for tweet_id in tweet_ids {
+    if previewed >= MAX_PREVIEWS {
+      unpreviewed_ids.push(tweet_id);
+      continue;
+    }
+
+    match send_preview(context, message, &tweet_id, mode, actions, &locale).await {
+      Ok(_) => {
+        previewed += 1;
+
+        info!(
+          "Applied preview | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id}",
+          tweet_id = tweet_id,
+          user_id = message.author.id.as_u64(),
+          guild_id = match message.guild_id {
+            Some(guild_id) => *guild_id.as_u64(),
+            None => 0
+          },
+        )
+      },
+      Err(why) => {
+        error!("Apply preview error: {:?}", why);
+        unpreviewed_ids.push(tweet_id);
+      }
+    }
+  }
+
+  unpreviewed_ids
+}
+
+pub async fn send_preview(
+  context: &Context,
+  message: &Message,
+  tweet_id: &str,
+  mode: ActionMode,
+  actions: &[TweetAction],
+  locale: &str
+) -> Result<Message> {
+  let preview: TweetPreview = TwitterClient::get_app_client()?
+    .get_tweet_preview(tweet_id)
+    .await?;
+
+  let embeds: Vec<CreateEmbed> = build_tweet_embeds(&preview, locale);
+  let tweet_ids: [Arc<str>; 1] = [preview.id.clone()];
+
+  let preview_message: Message = message.channel_id.send_message(
+    &context.http,
+    |reply: &mut CreateMessage<'_>| {
+      reply
+        .reference_message(message)
+        .allowed_mentions(
+          |mentions: &mut CreateAllowedMentions| {
+            mentions.replied_user(false)
+          }
+        )
+        .set_embeds(embeds);
+
+      if mode == ActionMode::Buttons {
+        reply.components(
+          |components: &mut CreateComponents| {
+            build_action_rows(components, &tweet_ids, actions)
+          }
+        );
+      }
+
+      reply
+    }
+  ).await?;
+
+  if mode == ActionMode::Reactions {
+    for action in actions {
+      preview_message.react(
+        &context.http,
+        ReactionType::Unicode(action.emoji().to_string())
+      ).await?;
+    }
+  }
+
+  Ok(preview_message)
+}
@@ -0,0 +1,630 @@
+use rust_i18n::t;
+use serenity::{
+  model::{
+    prelude::{
+      interaction::{
+        application_command::{
+          ApplicationCommandInteraction,
+          CommandDataOption,
+          CommandDataOptionValue
+        },
+        InteractionResponseType
+      },
+      command::CommandOptionType,
+      ChannelType
+    },
+    Permissions
+  },
+  prelude::Context,
+  builder::{
+    CreateApplicationCommand,
+    CreateApplicationCommandOption,
+    CreateInteractionResponse,
+    CreateInteractionResponseData,
+    CreateEmbed
+  },
+  utils::Color
+};
+use anyhow::{Result, anyhow};
+
+use crate::core::{
+  utils::EMBED_INFO_COLOR,
+  action::TweetAction,
+  lock::KeyedGuard,
+  settings::{GuildSettingsStore, GuildSettings, ActionMode, ChannelFilter}
+};
+
+
+fn get_option<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a CommandDataOption> {
+  options.iter().find(|option: &&CommandDataOption| option.name == name)
+}
+
+fn get_string_option<'a>(options: &'a [CommandDataOption], name: &str) -> Result<&'a str> {
+  get_option(options, name)
+    .and_then(|option: &CommandDataOption| option.value.as_ref()?.as_str())
+    .ok_or(anyhow!("Missing option `{}`.", name))
+}
+
+fn get_bool_option(options: &[CommandDataOption], name: &str) -> Result<bool> {
+  get_option(options, name)
+    .and_then(|option: &CommandDataOption| option.value.as_ref()?.as_bool())
+    .ok_or(anyhow!("Missing option `{}`.", name))
+}
+
+fn get_channel_option(options: &[CommandDataOption], name: &str) -> Result<u64> {
+  match get_option(options, name).and_then(|option: &CommandDataOption| option.resolved.as_ref()) {
+    Some(CommandDataOptionValue::Channel(channel)) => Ok(*channel.id.as_u64()),
+    _ => Err(anyhow!("Missing option `{}`.", name))
+  }
+}
+
+fn get_toggle_name(enabled: bool) -> &'static str {
+  match enabled {
+    true => "enabled",
+    false => "disabled"
+  }
+}
+
+fn build_settings_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  settings: &GuildSettings,
+  locale: &str
+) -> &'a mut CreateEmbed {
+  let actions: String = match settings.enabled_actions().as_slice() {
+    [] => t!("command.settings.show-embed.none", locale = locale),
+    actions => actions
+      .iter()
+      .map(|action: &TweetAction| action.emoji())
+      .collect::<Vec<&str>>()
+      .join(" ")
+  };
+
+  let channels: String = match settings.channels.is_empty() {
+    true => t!("command.settings.show-embed.none", locale = locale),
+    false => settings.channels
+      .iter()
+      .map(|channel_id: &u64| format!("<#{channel_id}>"))
+      .collect::<Vec<String>>()
+      .join(" ")
+  };
+
+  embed
+    .color(Color::new(EMBED_INFO_COLOR))
+    .title(t!("command.settings.show-embed.title", locale = locale))
+    .field(
+      t!("command.settings.show-embed.mode", locale = locale),
+      t!(&format!("command.settings.mode.{}", settings.mode.name()), locale = locale),
+      true
+    )
+    .field(
+      t!("command.settings.show-embed.auto-reactions", locale = locale),
+      t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.auto_reactions)), locale = locale),
+      true
+    )
+    .field(
+      t!("command.settings.show-embed.previews", locale = locale),
+      t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.previews)), locale = locale),
+      true
+    )
+    .field(
+      t!("command.settings.show-embed.actions", locale = locale),
+      actions,
+      true
+    )
+    .field(
+      t!("command.settings.show-embed.quoted-links", locale = locale),
+      t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.quoted_links)), locale = locale),
+      true
+    )
+    .field(
+      t!("command.settings.show-embed.spoiler-links", locale = locale),
+      t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.spoiler_links)), locale = locale),
+      true
+    )
+    .field(
+      t!(
+        "command.settings.show-embed.channels",
+        locale = locale,
+        filter = t!(&format!("command.settings.filter.{}", settings.channel_filter.name()), locale = locale)
+      ),
+      channels,
+      false
+    )
+}
+
+fn apply_option(
+  settings: &mut GuildSettings,
+  option: &CommandDataOption,
+  locale: &str
+) -> Result<Option<String>> {
+  match (option.name.as_str(), option.options.first()) {
+    ("show", _) => Ok(None),
+    ("mode", _) => {
+      settings.mode = ActionMode::from_name(get_string_option(&option.options, "mode")?)
+        .ok_or(anyhow!("Invalid action mode."))?;
+
+      Ok(
+        Some(
+          t!(
+            "command.settings.success-embed.mode",
+            locale = locale,
+            mode = t!(&format!("command.settings.mode.{}", settings.mode.name()), locale = locale)
+          )
+        )
+      )
+    },
+    ("auto-reactions", _) => {
+      settings.auto_reactions = get_bool_option(&option.options, "enabled")?;
+
+      Ok(
+        Some(
+          t!(
+            &format!("command.settings.success-embed.auto-reactions-{}", get_toggle_name(settings.auto_reactions)),
+            locale = locale
+          )
+        )
+      )
+    },
+    ("previews", _) => {
+      settings.previews = get_bool_option(&option.options, "enabled")?;
+
+      Ok(
+        Some(
+          t!(
+            &format!("command.settings.success-embed.previews-{}", get_toggle_name(settings.previews)),
+            locale = locale
+          )
+        )
+      )
+    },
+    ("action", _) => {
+      let action: TweetAction = TweetAction::from_name(get_string_option(&option.options, "action")?)
+        .ok_or(anyhow!("Invalid action."))?;
+      let enabled: bool = get_bool_option(&option.options, "enabled")?;
+
+      match enabled {
+        true => settings.disabled_actions.remove(&action),
+        false => settings.disabled_actions.insert(action)
+      };
+
+      Ok(
+        Some(
+          t!(
+            &format!("command.settings.success-embed.action-{}", get_toggle_name(enabled)),
+            locale = locale,
+            action = action.emoji()
+          )
+        )
+      )
+    },
+    ("links", _) => {
+      settings.quoted_links = get_bool_option(&option.options, "quotes").unwrap_or(settings.quoted_links);
+      settings.spoiler_links = get_bool_option(&option.options, "spoilers").unwrap_or(settings.spoiler_links);
+
+      Ok(
+        Some(
+          t!(
+            "command.settings.success-embed.links",
+            locale = locale,
+            quotes = t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.quoted_links)), locale = locale),
+            spoilers = t!(&format!("command.settings.toggle.{}", get_toggle_name(settings.spoiler_links)), locale = locale)
+          )
+        )
+      )
+    },
+    ("channels", Some(subcommand)) => {
+      let filter: String = t!(
+        &format!("command.settings.filter.{}", settings.channel_filter.name()),
+        locale = locale
+      );
+
+      match subcommand.name.as_str() {
+        "add" => {
+          let channel_id: u64 = get_channel_option(&subcommand.options, "channel")?;
+          settings.channels.insert(channel_id);
+
+          Ok(
+            Some(
+              t!(
+                "command.settings.success-embed.channel-added",
+                locale = locale,
+                channel_id = channel_id,
+                filter = filter
+              )
+            )
+          )
+        },
+        "remove" => {
+          let channel_id: u64 = get_channel_option(&subcommand.options, "channel")?;
+          settings.channels.remove(&channel_id);
+
+          Ok(
+            Some(
+              t!(
+                "command.settings.success-embed.channel-removed",
+                locale = locale,
+                channel_id = channel_id,
+                filter = filter
+              )
+            )
+          )
+        },
+        "filter" => {
+          settings.channel_filter = ChannelFilter::from_name(get_string_option(&subcommand.options, "filter")?)
+            .ok_or(anyhow!("Invalid channel filter."))?;
+
+          Ok(
+            Some(
+              t!(
+                &format!("command.settings.success-embed.filter-{}", settings.channel_filter.name()),
+                locale = locale
+              )
+            )
+          )
+        },
+        name => Err(anyhow!("Unknown settings subcommand `channels {}`.", name))
+      }
+    },
+    (name, _) => Err(anyhow!("Unknown settings subcommand `{}`.", name))
+  }
+}
+
+
+pub async fn execute(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction
+) -> Result<()> {
+  let guild_id: u64 = *interaction.guild_id
+    .ok_or(anyhow!("Settings used outside of a guild."))?
+    .as_u64();
+
+  let option: &CommandDataOption = interaction.data.options
+    .first()
+    .ok_or(anyhow!("Missing settings subcommand."))?;
+
+  let (settings, description): (GuildSettings, Option<String>) = {
+    let _updating: KeyedGuard<'_, u64> = GuildSettingsStore::get().lock(guild_id).await;
+    let mut settings: GuildSettings = GuildSettingsStore::get().load(guild_id).await?;
+
+    let description: Option<String> = apply_option(&mut settings, option, &interaction.locale)?;
+
+    if description.is_some() {
+      GuildSettingsStore::get().save(guild_id, settings.clone()).await?;
+    }
+
+    (settings, description)
+  };
+
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      response
+        .kind(InteractionResponseType::ChannelMessageWithSource)
+        .interaction_response_data(
+          |message: &mut CreateInteractionResponseData<'_>| {
+            message
+              .ephemeral(true)
+              .embed(
+                |embed: &mut CreateEmbed| {
+                  match &description {
+                    Some(description) => embed
+                      .color(Color::new(EMBED_INFO_COLOR))
+                      .title(
+                        t!(
+                          "command.settings.success-embed.title",
+                          locale = &interaction.locale
+                        )
+                      )
+                      .description(description),
+                    None => build_settings_embed(embed, &settings, &interaction.locale)
+                  }
+                }
+              )
+          }
+        )
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub fn register(
+  command: &mut CreateApplicationCommand
+) -> &mut CreateApplicationCommand {
+  command
+    .name("settings")
+    .name_localized("zh-TW", "設定")
+    .name_localized("zh-CN", "设置")
+    .description("Configure Twitcord for this server")
+    .description_localized("zh-TW", "設定此伺服器的 Twitcord")
+    .description_localized("zh-CN", "设置此服务器的 Twitcord")
+    .default_member_permissions(Permissions::MANAGE_GUILD)
+    .dm_permission(false)
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("show")
+          .name_localized("zh-TW", "檢視")
+          .name_localized("zh-CN", "查看")
+          .description("Show the current settings of this server")
+          .description_localized("zh-TW", "顯示此伺服器目前的設定")
+          .description_localized("zh-CN", "显示此服务器当前的设置")
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("mode")
+          .name_localized("zh-TW", "模式")
+          .name_localized("zh-CN", "模式")
+          .description("Choose how members interact with tweets")
+          .description_localized("zh-TW", "選擇成員與推文互動的方式")
+          .description_localized("zh-CN", "选择成员与推文互动的方式")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::String)
+                .name("mode")
+                .name_localized("zh-TW", "模式")
+                .name_localized("zh-CN", "模式")
+                .description("Action mode")
+                .description_localized("zh-TW", "操作模式")
+                .description_localized("zh-CN", "操作模式")
+                .required(true)
+                .add_string_choice_localized(
+                  "Reactions",
+                  ActionMode::Reactions.name(),
+                  [("zh-TW", "表情符號反應"), ("zh-CN", "表情符号回应")]
+                )
+                .add_string_choice_localized(
+                  "Buttons",
+                  ActionMode::Buttons.name(),
+                  [("zh-TW", "按鈕"), ("zh-CN", "按钮")]
+                )
+            }
+          )
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("auto-reactions")
+          .name_localized("zh-TW", "自動反應")
+          .name_localized("zh-CN", "自动回应")
+          .description("Add actions to tweet links automatically")
+          .description_localized("zh-TW", "自動為推文連結加上操作")
+          .description_localized("zh-CN", "自动为推文链接加上操作")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("enabled")
+                .name_localized("zh-TW", "啟用")
+                .name_localized("zh-CN", "启用")
+                .description("Enable auto reactions")
+                .description_localized("zh-TW", "啟用自動反應")
+                .description_localized("zh-CN", "启用自动回应")
+                .required(true)
+            }
+          )
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("previews")
+          .name_localized("zh-TW", "預覽")
+          .name_localized("zh-CN", "预览")
+          .description("Post a preview of linked tweets")
+          .description_localized("zh-TW", "為推文連結發送預覽")
+          .description_localized("zh-CN", "为推文链接发送预览")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("enabled")
+                .name_localized("zh-TW", "啟用")
+                .name_localized("zh-CN", "启用")
+                .description("Enable tweet previews")
+                .description_localized("zh-TW", "啟用推文預覽")
+                .description_localized("zh-CN", "启用推文预览")
+                .required(true)
+            }
+          )
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("action")
+          .name_localized("zh-TW", "操作")
+          .name_localized("zh-CN", "操作")
+          .description("Choose which actions are offered on tweet links")
+          .description_localized("zh-TW", "選擇推文連結提供的操作")
+          .description_localized("zh-CN", "选择推文链接提供的操作")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::String)
+                .name("action")
+                .name_localized("zh-TW", "操作")
+                .name_localized("zh-CN", "操作")
+                .description("Tweet action")
+                .description_localized("zh-TW", "推文操作")
+                .description_localized("zh-CN", "推文操作")
+                .required(true)
+                .add_string_choice_localized(
+                  "❤️ Like",
+                  TweetAction::Like.name(),
+                  [("zh-TW", "❤️ 喜歡"), ("zh-CN", "❤️ 喜欢")]
+                )
+                .add_string_choice_localized(
+                  "🔁 Retweet",
+                  TweetAction::Retweet.name(),
+                  [("zh-TW", "🔁 轉推"), ("zh-CN", "🔁 转推")]
+                )
+                .add_string_choice_localized(
+                  "🔖 Bookmark",
+                  TweetAction::Bookmark.name(),
+                  [("zh-TW", "🔖 書籤"), ("zh-CN", "🔖 书签")]
+                )
+                .add_string_choice_localized(
+                  "📡 Follow",
+                  TweetAction::Follow.name(),
+                  [("zh-TW", "📡 跟隨"), ("zh-CN", "📡 关注")]
+                )
+            }
+          )
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("enabled")
+                .name_localized("zh-TW", "啟用")
+                .name_localized("zh-CN", "启用")
+                .description("Offer this action")
+                .description_localized("zh-TW", "提供此操作")
+                .description_localized("zh-CN", "提供此操作")
+                .required(true)
+            }
+          )
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommand)
+          .name("links")
+          .name_localized("zh-TW", "連結")
+          .name_localized("zh-CN", "链接")
+          .description("Choose whether tweet links in quotes and spoilers are detected")
+          .description_localized("zh-TW", "選擇是否偵測引言與防雷標記中的推文連結")
+          .description_localized("zh-CN", "选择是否检测引用与剧透标记中的推文链接")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("quotes")
+                .name_localized("zh-TW", "引言")
+                .name_localized("zh-CN", "引用")
+                .description("Detect tweet links in quotes")
+                .description_localized("zh-TW", "偵測引言中的推文連結")
+                .description_localized("zh-CN", "检测引用中的推文链接")
+            }
+          )
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::Boolean)
+                .name("spoilers")
+                .name_localized("zh-TW", "防雷")
+                .name_localized("zh-CN", "剧透")
+                .description("Detect tweet links in spoilers")
+                .description_localized("zh-TW", "偵測防雷標記中的推文連結")
+                .description_localized("zh-CN", "检测剧透标记中的推文链接")
+            }
+          )
+      }
+    )
+    .create_option(
+      |option: &mut CreateApplicationCommandOption| {
+        option
+          .kind(CommandOptionType::SubCommandGroup)
+          .name("channels")
+          .name_localized("zh-TW", "頻道")
+          .name_localized("zh-CN", "频道")
+          .description("Choose where Twitcord is active")
+          .description_localized("zh-TW", "選擇 Twitcord 啟用的頻道")
+          .description_localized("zh-CN", "选择 Twitcord 启用的频道")
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::SubCommand)
+                .name("add")
+                .name_localized("zh-TW", "新增")
+                .name_localized("zh-CN", "添加")
+                .description("Add a channel to the channel list")
+                .description_localized("zh-TW", "將頻道加入頻道清單")
+                .description_localized("zh-CN", "将频道加入频道列表")
+                .create_sub_option(
+                  |option: &mut CreateApplicationCommandOption| {
+                    register_channel_option(option)
+                  }
+                )
+            }
+          )
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::SubCommand)
+                .name("remove")
+                .name_localized("zh-TW", "移除")
+                .name_localized("zh-CN", "移除")
+                .description("Remove a channel from the channel list")
+                .description_localized("zh-TW", "將頻道移出頻道清單")
+                .description_localized("zh-CN", "将频道移出频道列表")
+                .create_sub_option(
+                  |option: &mut CreateApplicationCommandOption| {
+                    register_channel_option(option)
+                  }
+                )
+            }
+          )
+          .create_sub_option(
+            |option: &mut CreateApplicationCommandOption| {
+              option
+                .kind(CommandOptionType::SubCommand)
+                .name("filter")
+                .name_localized("zh-TW", "篩選")
+                .name_localized("zh-CN", "筛选")
+                .description("Choose whether the channel list is an allowlist or a denylist")
+                .description_localized("zh-TW", "選擇頻道清單為允許清單或封鎖清單")
+                .description_localized("zh-CN", "选择频道列表为允许列表或屏蔽列表")
+                .create_sub_option(
+                  |option: &mut CreateApplicationCommandOption| {
+                    option
+                      .kind(CommandOptionType::String)
+                      .name("filter")
+                      .name_localized("zh-TW", "篩選")
+                      .name_localized("zh-CN", "筛选")
+                      .description("Channel filter")
+                      .description_localized("zh-TW", "頻道篩選")
+                      .description_localized("zh-CN", "频道筛选")
+                      .required(true)
+                      .add_string_choice_localized(
+                        "Denylist",
+                        ChannelFilter::Denylist.name(),
+                        [("zh-TW", "封鎖清單"), ("zh-CN", "屏蔽列表")]
+                      )
+                      .add_string_choice_localized(
+                        "Allowlist",
+                        ChannelFilter::Allowlist.name(),
+                        [("zh-TW", "允許清單"), ("zh-CN", "允许列表")]
+                      )
+                  }
+                )
+            }
+          )
+      }
+    )
+}
+
+fn register_channel_option(
+  option: &mut CreateApplicationCommandOption
+) -> &mut CreateApplicationCommandOption {
+  option
+    .kind(CommandOptionType::Channel)
+    .name("channel")
+    .name_localized("zh-TW", "頻道")
+    .name_localized("zh-CN", "频道")
+    .description("Text channel")
+    .description_localized("zh-TW", "文字頻道")
+    .description_localized("zh-CN", "文字频道")
+    .channel_types(&[ChannelType::Text, ChannelType::News, ChannelType::PublicThread, ChannelType::PrivateThread])
+    .required(true)
+}
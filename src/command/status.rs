@@ -0,0 +1,250 @@
+use rust_i18n::t;
+use serenity::{
+  model::prelude::{
+    interaction::{
+      application_command::ApplicationCommandInteraction,
+      InteractionResponseType
+    },
+    GuildId
+  },
+  prelude::Context,
+  builder::{
+    CreateApplicationCommand,
+    CreateInteractionResponse,
+    CreateInteractionResponseData,
+    CreateInteractionResponseFollowup,
+    CreateEmbed,
+    CreateEmbedAuthor
+  },
+  utils::Color
+};
+use tracing::log::warn;
+use anyhow::Result;
+
+use crate::core::{
+  utils::{EMBED_INFO_COLOR, EMBED_ERROR_COLOR},
+  oauth::TwitterClient,
+  cache::{AccessTokenCache, CacheData},
+  store::{StoredToken, Credential},
+  model::{TwitterUser, get_tweet_url},
+  action::{TweetAction, ActionFailure, get_recent_failures},
+  error::TwitterError
+};
+
+
+fn build_not_connected_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  locale: &str
+) -> &'a mut CreateEmbed {
+  embed
+    .color(Color::new(EMBED_ERROR_COLOR))
+    .title(t!("command.status.not-connected-embed.title", locale = locale))
+    .description(t!("command.status.not-connected-embed.description", locale = locale))
+}
+
+fn build_status_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  token: &StoredToken,
+  verified: &Result<(TwitterUser, Vec<TweetAction>), TwitterError>,
+  failures: &[ActionFailure],
+  locale: &str
+) -> &'a mut CreateEmbed {
+  embed.title(t!("command.status.status-embed.title", locale = locale));
+
+  match verified {
+    Ok((account, _)) => {
+      embed
+        .color(Color::new(EMBED_INFO_COLOR))
+        .author(
+          |author: &mut CreateEmbedAuthor| {
+            author
+              .name(format!("@{}", account.username))
+              .url(account.url());
+
+            if let Some(profile_image_url) = &account.profile_image_url {
+              author.icon_url(profile_image_url);
+            }
+
+            author
+          }
+        );
+
+      if let Some(profile_image_url) = &account.profile_image_url {
+        embed.thumbnail(profile_image_url);
+      }
+    },
+    Err(_) => {
+      embed.color(Color::new(EMBED_ERROR_COLOR));
+    }
+  }
+
+  embed
+    .field(
+      t!("command.status.status-embed.account", locale = locale),
+      match (verified, token.is_identified()) {
+        (Ok((account, _)), _) => format!("[@{username}]({url})", username = account.username, url = account.url()),
+        (Err(_), true) => format!("@{}", token.screen_name),
+        (Err(_), false) => t!("command.status.status-embed.unknown", locale = locale)
+      },
+      true
+    )
+    .field(
+      t!("command.status.status-embed.credential", locale = locale),
+      match verified {
+        Ok(_) => t!("command.status.status-embed.credential-valid", locale = locale),
+        Err(error) => t!("command.status.status-embed.credential-invalid", locale = locale, code = error.code())
+      },
+      true
+    )
+    .field(
+      t!("command.status.status-embed.connected-at", locale = locale),
+      format!("<t:{}:R>", token.created_at),
+      true
+    );
+
+  if let Credential::OAuth2 { expires_at, .. } = &token.credential {
+    embed.field(
+      t!("command.status.status-embed.expires-at", locale = locale),
+      format!("<t:{}:R>", expires_at),
+      true
+    );
+  }
+
+  if let Ok((_, actions)) = verified {
+    embed.field(
+      t!("command.status.status-embed.actions", locale = locale),
+      TweetAction::ALL
+        .iter()
+        .map(
+          |action: &TweetAction| format!(
+            "{emoji} {state}",
+            emoji = action.emoji(),
+            state = match actions.contains(action) {
+              true => ":white_check_mark:",
+              false => ":x:"
+            }
+          )
+        )
+        .collect::<Vec<String>>()
+        .join("\n"),
+      false
+    );
+  }
+
+  embed.field(
+    t!("command.status.status-embed.failures", locale = locale),
+    match failures.is_empty() {
+      true => t!("command.status.status-embed.none", locale = locale),
+      false => failures
+        .iter()
+        .rev()
+        .map(
+          |failure: &ActionFailure| format!(
+            "<t:{failed_at}:R> {emoji} [{tweet_id}]({tweet_url}) `{code}`",
+            failed_at = failure.failed_at,
+            emoji = failure.action.emoji(),
+            tweet_id = failure.tweet_id,
+            tweet_url = get_tweet_url(&failure.tweet_id),
+            code = failure.error.code()
+          )
+        )
+        .collect::<Vec<String>>()
+        .join("\n")
+    },
+    false
+  )
+}
+
+async fn verify_token(
+  user_id: u64,
+  token: &StoredToken
+) -> Result<(TwitterUser, Vec<TweetAction>)> {
+  let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, token).await?;
+  let account: TwitterUser = twitter_client.get_me().await?;
+
+  let actions: Vec<TweetAction> = TweetAction::ALL
+    .into_iter()
+    .filter(|action: &TweetAction| twitter_client.supports(*action))
+    .collect();
+
+  Ok((account, actions))
+}
+
+
+pub async fn execute(
+  context: &Context,
+  interaction: &ApplicationCommandInteraction
+) -> Result<()> {
+  interaction.create_interaction_response(
+    &context.http,
+    |response: &mut CreateInteractionResponse<'_>| {
+      response
+        .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        .interaction_response_data(
+          |message: &mut CreateInteractionResponseData<'_>| {
+            message.ephemeral(true)
+          }
+        )
+    }
+  ).await?;
+
+  let user_id: u64 = *interaction.user.id.as_u64();
+
+  let token: Option<StoredToken> = AccessTokenCache::get()
+    .request(user_id)
+    .await?
+    .and_then(
+      |cache_data: CacheData| cache_data.accounts
+        .select(interaction.guild_id.map(|guild_id: GuildId| *guild_id.as_u64()))
+        .cloned()
+    );
+
+  let mut embed: CreateEmbed = CreateEmbed::default();
+
+  match &token {
+    Some(token) => {
+      let verified: Result<(TwitterUser, Vec<TweetAction>), TwitterError> = verify_token(user_id, token)
+        .await
+        .map_err(
+          |why: anyhow::Error| {
+            warn!("Verify credential failed: {:?} | User: {}", why, user_id);
+            TwitterError::classify(&why)
+          }
+        );
+
+      build_status_embed(
+        &mut embed,
+        token,
+        &verified,
+        &get_recent_failures(user_id),
+        &interaction.locale
+      );
+    },
+    None => {
+      build_not_connected_embed(&mut embed, &interaction.locale);
+    }
+  }
+
+  interaction.create_followup_message(
+    &context.http,
+    |followup: &mut CreateInteractionResponseFollowup<'_>| {
+      followup
+        .ephemeral(true)
+        .add_embed(embed)
+    }
+  ).await?;
+
+  Ok(())
+}
+
+pub fn register(
+  command: &mut CreateApplicationCommand
+) -> &mut CreateApplicationCommand {
+  command
+    .name("status")
+    .name_localized("zh-TW", "狀態")
+    .name_localized("zh-CN", "状态")
+    .description("Check your connected Twitter account")
+    .description_localized("zh-TW", "檢查已連接的 Twitter 帳號")
+    .description_localized("zh-CN", "检查已连接的 Twitter 账号")
+}
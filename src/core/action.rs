@@ -0,0 +1,281 @@
+use std::{
+  sync::{Arc, Mutex, MutexGuard, OnceLock},
+  collections::HashMap
+};
+
+use serde::{Serialize, Deserialize};
+use anyhow::Result;
+
+use super::{
+  store::get_database,
+  oauth::TwitterClient,
+  oauth2::{AuthFlow, get_auth_flow},
+  error::TwitterError,
+  model::{Like, Retweet, Bookmark, Friendship},
+  utils::current_time
+};
+
+
+pub static TOGGLE_STATE_MAX_AGE: u64 = 2592000;
+pub static ACTION_FAILURE_MAX_AGE: u64 = 86400;
+pub static MAX_ACTION_FAILURES: usize = 5;
+
+static TOGGLE_STATE: OnceLock<sled::Tree> = OnceLock::new();
+static ACTION_FAILURES: OnceLock<Mutex<HashMap<u64, Vec<ActionFailure>>>> = OnceLock::new();
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TweetAction {
+  Like,
+  Retweet,
+  Bookmark,
+  Follow
+}
+
+impl TweetAction {
+  pub const ALL: [TweetAction; 4] = [
+    TweetAction::Like,
+    TweetAction::Retweet,
+    TweetAction::Bookmark,
+    TweetAction::Follow
+  ];
+
+  pub fn from_emoji(emoji: &str) -> Option<TweetAction> {
+    match emoji {
+      "❤️" => Some(TweetAction::Like),
+      "🔁" => Some(TweetAction::Retweet),
+      "🔖" => Some(TweetAction::Bookmark),
+      "📡" => Some(TweetAction::Follow),
+      _ => None
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<TweetAction> {
+    match name {
+      "like" => Some(TweetAction::Like),
+      "retweet" => Some(TweetAction::Retweet),
+      "bookmark" => Some(TweetAction::Bookmark),
+      "follow" => Some(TweetAction::Follow),
+      _ => None
+    }
+  }
+
+  pub fn emoji(self: &Self) -> &'static str {
+    match self {
+      TweetAction::Like => "❤️",
+      TweetAction::Retweet => "🔁",
+      TweetAction::Bookmark => "🔖",
+      TweetAction::Follow => "📡"
+    }
+  }
+
+  pub fn name(self: &Self) -> &'static str {
+    match self {
+      TweetAction::Like => "like",
+      TweetAction::Retweet => "retweet",
+      TweetAction::Bookmark => "bookmark",
+      TweetAction::Follow => "follow"
+    }
+  }
+
+  pub fn scope(self: &Self) -> &'static str {
+    match self {
+      TweetAction::Like => "like.write",
+      TweetAction::Retweet => "tweet.write",
+      TweetAction::Bookmark => "bookmark.write",
+      TweetAction::Follow => "follows.write"
+    }
+  }
+
+  pub fn is_available(self: &Self) -> bool {
+    match self {
+      TweetAction::Bookmark => get_auth_flow() == AuthFlow::OAuth2,
+      _ => true
+    }
+  }
+
+  pub async fn invoke(
+    self: &Self,
+    twitter_client: &mut TwitterClient,
+    tweet_id: &str
+  ) -> Result<bool> {
+    match self {
+      TweetAction::Like => twitter_client.like(tweet_id).await.map(|like: Like| like.liked),
+      TweetAction::Retweet => twitter_client.retweet(tweet_id).await.map(|retweet: Retweet| retweet.retweeted),
+      TweetAction::Bookmark => twitter_client.bookmark(tweet_id).await.map(|bookmark: Bookmark| bookmark.bookmarked),
+      TweetAction::Follow => {
+        let author_id: Arc<str> = twitter_client.get_author_id(tweet_id).await?;
+
+        twitter_client.follow(&author_id).await.map(
+          |friendship: Friendship| friendship.following || friendship.pending_follow
+        )
+      }
+    }
+  }
+
+  pub async fn revoke(
+    self: &Self,
+    twitter_client: &mut TwitterClient,
+    tweet_id: &str
+  ) -> Result<bool> {
+    match self {
+      TweetAction::Like => twitter_client.unlike(tweet_id).await.map(|like: Like| !like.liked),
+      TweetAction::Retweet => twitter_client.unretweet(tweet_id).await.map(|retweet: Retweet| !retweet.retweeted),
+      TweetAction::Bookmark => twitter_client.unbookmark(tweet_id).await.map(|bookmark: Bookmark| !bookmark.bookmarked),
+      TweetAction::Follow => {
+        let author_id: Arc<str> = twitter_client.get_author_id(tweet_id).await?;
+
+        twitter_client.unfollow(&author_id).await.map(
+          |friendship: Friendship| !friendship.following
+        )
+      }
+    }
+  }
+
+  pub async fn toggle(
+    self: &Self,
+    twitter_client: &mut TwitterClient,
+    user_id: u64,
+    tweet_id: &str
+  ) -> Result<ToggleResult> {
+    let key: Vec<u8> = self.toggle_key(user_id, tweet_id);
+
+    let result: ToggleResult = match TweetAction::take_toggle_state(&key)? {
+      true => ToggleResult::Revoked(self.revoke(twitter_client, tweet_id).await?),
+      false => match self.invoke(twitter_client, tweet_id).await {
+        Ok(applied) => ToggleResult::Invoked(applied),
+        Err(why) if TwitterError::classify(&why) == TwitterError::AlreadyDone => {
+          ToggleResult::Revoked(self.revoke(twitter_client, tweet_id).await?)
+        },
+        Err(why) => return Err(why)
+      }
+    };
+
+    if let ToggleResult::Invoked(true) = result {
+      TweetAction::put_toggle_state(user_id, key)?;
+    }
+
+    Ok(result)
+  }
+
+  pub fn record_failure(self: &Self, user_id: u64, tweet_id: &str, error: &anyhow::Error) {
+    let error: TwitterError = TwitterError::classify(error);
+
+    if error == TwitterError::AlreadyDone {
+      return;
+    }
+
+    let current_time: u64 = current_time();
+
+    let mut action_failures: MutexGuard<HashMap<u64, Vec<ActionFailure>>> = action_failures();
+    action_failures.retain(
+      |_, failures: &mut Vec<ActionFailure>| {
+        failures.retain(|failure: &ActionFailure| current_time.saturating_sub(failure.failed_at) <= ACTION_FAILURE_MAX_AGE);
+        !failures.is_empty()
+      }
+    );
+
+    let failures: &mut Vec<ActionFailure> = action_failures.entry(user_id).or_default();
+    failures.push(
+      ActionFailure {
+        action: *self,
+        tweet_id: tweet_id.into(),
+        error,
+        failed_at: current_time
+      }
+    );
+
+    if failures.len() > MAX_ACTION_FAILURES {
+      failures.remove(0);
+    }
+  }
+
+  fn toggle_key(self: &Self, user_id: u64, tweet_id: &str) -> Vec<u8> {
+    [&user_id.to_be_bytes(), self.name().as_bytes(), b":", tweet_id.as_bytes()].concat()
+  }
+
+  fn take_toggle_state(key: &[u8]) -> Result<bool> {
+    let removed: Option<sled::IVec> = TweetAction::toggle_state().remove(key)?;
+    TweetAction::toggle_state().flush()?;
+
+    Ok(
+      removed
+        .and_then(|applied_at: sled::IVec| applied_at.as_ref().try_into().ok().map(u64::from_be_bytes))
+        .is_some_and(|applied_at: u64| current_time().saturating_sub(applied_at) <= TOGGLE_STATE_MAX_AGE)
+    )
+  }
+
+  fn put_toggle_state(user_id: u64, key: Vec<u8>) -> Result<()> {
+    let current_time: u64 = current_time();
+    let toggle_state: &sled::Tree = TweetAction::toggle_state();
+
+    for entry in toggle_state.scan_prefix(user_id.to_be_bytes()) {
+      let (stale_key, applied_at): (sled::IVec, sled::IVec) = entry?;
+      let is_expired: bool = applied_at
+        .as_ref()
+        .try_into()
+        .map(|applied_at: [u8; 8]| current_time.saturating_sub(u64::from_be_bytes(applied_at)) > TOGGLE_STATE_MAX_AGE)
+        .unwrap_or(true);
+
+      if is_expired {
+        toggle_state.remove(stale_key)?;
+      }
+    }
+
+    toggle_state.insert(key, &current_time.to_be_bytes())?;
+    toggle_state.flush()?;
+
+    Ok(())
+  }
+
+  fn toggle_state() -> &'static sled::Tree {
+    TOGGLE_STATE.get_or_init(
+      || {
+        get_database()
+          .open_tree("toggle_state")
+          .expect("Open toggle state failed.")
+      }
+    )
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleResult {
+  Invoked(bool),
+  Revoked(bool)
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ActionFailure {
+  pub action: TweetAction,
+  pub tweet_id: Arc<str>,
+  pub error: TwitterError,
+  pub failed_at: u64
+}
+
+
+pub fn get_recent_failures(user_id: u64) -> Vec<ActionFailure> {
+  let current_time: u64 = current_time();
+
+  action_failures()
+    .get(&user_id)
+    .map(
+      |failures: &Vec<ActionFailure>| failures
+        .iter()
+        .filter(|failure: &&ActionFailure| current_time.saturating_sub(failure.failed_at) <= ACTION_FAILURE_MAX_AGE)
+        .cloned()
+        .collect()
+    )
+    .unwrap_or_default()
+}
+
+
+fn action_failures() -> MutexGuard<'static, HashMap<u64, Vec<ActionFailure>>> {
+  ACTION_FAILURES
+    .get_or_init(|| Mutex::new(HashMap::new()))
+    .lock()
+    .expect("Action failures poisoned.")
+}
@@ -0,0 +1,526 @@
+use std::{
+  collections::BTreeMap,
+  sync::{OnceLock, Arc},
+  fmt::Debug,
+  env
+};
+
+use hyper::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use serenity::async_trait;
+use tokio::sync::Mutex;
+use tracing::debug;
+use anyhow::{Result, anyhow};
+
+use super::{
+  oauth::{OAuthSession, RequestBody, AuthScheme},
+  error::TwitterError,
+  action::TweetAction,
+  ttl::TtlCache,
+  model::{
+    Tweet,
+    TweetPreview,
+    TwitterUser,
+    Like,
+    Retweet,
+    Bookmark,
+    Friendship,
+    TwitterApiError,
+    get_tweet_url,
+    V1Tweet,
+    V1TweetPreview,
+    V1User,
+    V2Response,
+    V2Includes,
+    V2Tweet,
+    V2TweetPreview,
+    V2User
+  }
+};
+
+
+static V2_USER_ID_CACHE_MAX_AGE: u64 = 7200;
+static V2_USER_ID_CACHE_MAX_ITEM: usize = 1000;
+static V2_USER_ID_CACHE: OnceLock<Mutex<TtlCache<Arc<str>, Arc<str>>>> = OnceLock::new();
+
+pub static TWITTER_API: OnceLock<Arc<dyn TwitterApi>> = OnceLock::new();
+
+
+pub fn get_api() -> Arc<dyn TwitterApi> {
+  TWITTER_API.get_or_init(
+    || {
+      match env::var("TWITTER_API_VERSION").as_deref() {
+        Ok("2") => Arc::new(TwitterApiV2),
+        Ok("1.1") | Err(_) => Arc::new(TwitterApiV1),
+        Ok(version) => panic!("Unsupported TWITTER_API_VERSION `{}`.", version)
+      }
+    }
+  ).clone()
+}
+
+pub async fn forget_user_id(access_token: &str) {
+  if let Some(cache) = V2_USER_ID_CACHE.get() {
+    cache.lock().await.remove(&Arc::from(access_token));
+  }
+}
+
+
+#[async_trait]
+pub trait TwitterApi: Debug + Send + Sync {
+  async fn like(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like>;
+
+  async fn unlike(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like>;
+
+  async fn retweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet>;
+
+  async fn unretweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet>;
+
+  async fn bookmark(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Bookmark>;
+
+  async fn unbookmark(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Bookmark>;
+
+  async fn follow(self: &Self, oauth: &mut OAuthSession, user_id: &str) -> Result<Friendship>;
+
+  async fn unfollow(self: &Self, oauth: &mut OAuthSession, user_id: &str) -> Result<Friendship>;
+
+  async fn get_tweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Tweet>;
+
+  async fn get_tweet_preview(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<TweetPreview>;
+
+  async fn get_me(self: &Self, oauth: &mut OAuthSession) -> Result<TwitterUser>;
+
+  async fn reply(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet>;
+
+  async fn quote(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet>;
+
+  fn supports(self: &Self, oauth: &OAuthSession, action: TweetAction) -> bool;
+}
+
+
+#[derive(Debug)]
+pub struct TwitterApiV1;
+
+impl TwitterApiV1 {
+  async fn send<T: DeserializeOwned>(
+    self: &Self,
+    oauth: &mut OAuthSession,
+    method: Method,
+    path: &str,
+    body: Option<RequestBody>
+  ) -> Result<T> {
+    let url: Arc<str> = format!(
+      "{base_url}/1.1/{path}",
+      base_url = oauth.base_url()
+    ).into();
+
+    Ok(
+      serde_json::from_str(
+        &oauth.request(method, &url, BTreeMap::new(), body).await?
+      )?
+    )
+  }
+}
+
+#[async_trait]
+impl TwitterApi for TwitterApiV1 {
+  async fn like(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like> {
+    self.send::<V1Tweet>(
+      oauth,
+      Method::POST,
+      &format!("favorites/create.json?id={tweet_id}"),
+      None
+    ).await?;
+
+    Ok(Like { liked: true })
+  }
+
+  async fn unlike(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like> {
+    self.send::<V1Tweet>(
+      oauth,
+      Method::POST,
+      &format!("favorites/destroy.json?id={tweet_id}"),
+      None
+    ).await?;
+
+    Ok(Like { liked: false })
+  }
+
+  async fn retweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet> {
+    self.send::<V1Tweet>(
+      oauth,
+      Method::POST,
+      &format!("statuses/retweet/{tweet_id}.json"),
+      None
+    ).await?;
+
+    Ok(Retweet { retweeted: true })
+  }
+
+  async fn unretweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet> {
+    self.send::<V1Tweet>(
+      oauth,
+      Method::POST,
+      &format!("statuses/unretweet/{tweet_id}.json"),
+      None
+    ).await?;
+
+    Ok(Retweet { retweeted: false })
+  }
+
+  async fn bookmark(self: &Self, _oauth: &mut OAuthSession, _tweet_id: &str) -> Result<Bookmark> {
+    Err(TwitterError::Unsupported.into())
+  }
+
+  async fn unbookmark(self: &Self, _oauth: &mut OAuthSession, _tweet_id: &str) -> Result<Bookmark> {
+    Err(TwitterError::Unsupported.into())
+  }
+
+  async fn follow(self: &Self, oauth: &mut OAuthSession, user_id: &str) -> Result<Friendship> {
+    let user: V1User = self.send(
+      oauth,
+      Method::POST,
+      &format!("friendships/create.json?user_id={user_id}"),
+      None
+    ).await?;
+
+    Ok(
+      Friendship {
+        following: !user.follow_request_sent,
+        pending_follow: user.follow_request_sent
+      }
+    )
+  }
+
+  async fn unfollow(self: &Self, oauth: &mut OAuthSession, user_id: &str) -> Result<Friendship> {
+    self.send::<V1User>(
+      oauth,
+      Method::POST,
+      &format!("friendships/destroy.json?user_id={user_id}"),
+      None
+    ).await?;
+
+    Ok(
+      Friendship {
+        following: false,
+        pending_follow: false
+      }
+    )
+  }
+
+  async fn get_tweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Tweet> {
+    self.send::<Vec<V1Tweet>>(
+      oauth,
+      Method::GET,
+      &format!("statuses/lookup.json?id={tweet_id}&trim_user=true"),
+      None
+    ).await?
+      .into_iter()
+      .find(|tweet: &V1Tweet| tweet.id_str.as_ref() == tweet_id)
+      .map(Tweet::from)
+      .ok_or(
+        TwitterApiError::new(StatusCode::NOT_FOUND, Some(144), "No status found with that ID.").into()
+      )
+  }
+
+  async fn get_tweet_preview(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<TweetPreview> {
+    Ok(
+      self.send::<V1TweetPreview>(
+        oauth,
+        Method::GET,
+        &format!("statuses/show.json?id={tweet_id}&tweet_mode=extended&include_entities=true"),
+        None
+      ).await?.into()
+    )
+  }
+
+  async fn get_me(self: &Self, oauth: &mut OAuthSession) -> Result<TwitterUser> {
+    Ok(
+      self.send::<V1User>(
+        oauth,
+        Method::GET,
+        "account/verify_credentials.json?skip_status=true",
+        None
+      ).await?.into()
+    )
+  }
+
+  async fn reply(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet> {
+    Ok(
+      self.send::<V1Tweet>(
+        oauth,
+        Method::POST,
+        "statuses/update.json",
+        Some(
+          RequestBody::Form(
+            vec![
+              ("status", text.into()),
+              ("in_reply_to_status_id", tweet_id.into()),
+              ("auto_populate_reply_metadata", "true".into())
+            ]
+          )
+        )
+      ).await?.into()
+    )
+  }
+
+  async fn quote(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet> {
+    Ok(
+      self.send::<V1Tweet>(
+        oauth,
+        Method::POST,
+        "statuses/update.json",
+        Some(
+          RequestBody::Form(
+            vec![
+              ("status", text.into()),
+              ("attachment_url", get_tweet_url(tweet_id))
+            ]
+          )
+        )
+      ).await?.into()
+    )
+  }
+
+  fn supports(self: &Self, oauth: &OAuthSession, action: TweetAction) -> bool {
+    action != TweetAction::Bookmark && oauth.is_permitted(action)
+  }
+}
+
+
+#[derive(Debug)]
+pub struct TwitterApiV2;
+
+impl TwitterApiV2 {
+  async fn send<T: DeserializeOwned>(
+    self: &Self,
+    oauth: &mut OAuthSession,
+    method: Method,
+    path: &str,
+    body: Option<Value>
+  ) -> Result<T> {
+    self.send_response(oauth, method, path, body).await.map(|(data, _)| data)
+  }
+
+  async fn send_response<T: DeserializeOwned>(
+    self: &Self,
+    oauth: &mut OAuthSession,
+    method: Method,
+    path: &str,
+    body: Option<Value>
+  ) -> Result<(T, V2Includes)> {
+    let url: Arc<str> = format!(
+      "{base_url}/2/{path}",
+      base_url = oauth.base_url()
+    ).into();
+
+    let response: V2Response<T> = serde_json::from_str(
+      &oauth.request(
+        method,
+        &url,
+        BTreeMap::new(),
+        body.map(|body: Value| RequestBody::Json(body.to_string().into()))
+      ).await?
+    )?;
+
+    match (response.data, response.errors.first()) {
+      (Some(data), _) => Ok((data, response.includes)),
+      (None, Some(problem)) => Err(TwitterApiError::from_problem(problem).into()),
+      (None, None) => Err(anyhow!("Missing response data."))
+    }
+  }
+
+  async fn get_user_id(self: &Self, oauth: &mut OAuthSession) -> Result<Arc<str>> {
+    let access_token: Arc<str> = oauth.resource_owner_key()?;
+
+    let cache: &Mutex<TtlCache<Arc<str>, Arc<str>>> = V2_USER_ID_CACHE.get_or_init(
+      || Mutex::new(TtlCache::new(V2_USER_ID_CACHE_MAX_AGE, V2_USER_ID_CACHE_MAX_ITEM))
+    );
+
+    if let Some(user_id) = cache.lock().await.get(&access_token) {
+      return Ok(user_id.clone());
+    }
+
+    let user: TwitterUser = self.get_me(oauth).await?;
+
+    debug!("Resolved v2 user `{}` | ID: {}", user.username, user.id);
+
+    cache.lock().await.insert(access_token, user.id.clone());
+
+    Ok(user.id)
+  }
+
+  async fn create_tweet(self: &Self, oauth: &mut OAuthSession, body: Value) -> Result<Tweet> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    let tweet: V2Tweet = self.send(
+      oauth,
+      Method::POST,
+      "tweets",
+      Some(body)
+    ).await?;
+
+    Ok(
+      Tweet {
+        author_id: tweet.author_id.unwrap_or(user_id),
+        id: tweet.id,
+        text: tweet.text
+      }
+    )
+  }
+}
+
+#[async_trait]
+impl TwitterApi for TwitterApiV2 {
+  async fn like(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::POST,
+      &format!("users/{user_id}/likes"),
+      Some(json!({ "tweet_id": tweet_id }))
+    ).await
+  }
+
+  async fn unlike(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Like> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::DELETE,
+      &format!("users/{user_id}/likes/{tweet_id}"),
+      None
+    ).await
+  }
+
+  async fn retweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::POST,
+      &format!("users/{user_id}/retweets"),
+      Some(json!({ "tweet_id": tweet_id }))
+    ).await
+  }
+
+  async fn unretweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Retweet> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::DELETE,
+      &format!("users/{user_id}/retweets/{tweet_id}"),
+      None
+    ).await
+  }
+
+  async fn bookmark(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Bookmark> {
+    if !self.supports(oauth, TweetAction::Bookmark) {
+      return Err(TwitterError::Unsupported.into());
+    }
+
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::POST,
+      &format!("users/{user_id}/bookmarks"),
+      Some(json!({ "tweet_id": tweet_id }))
+    ).await
+  }
+
+  async fn unbookmark(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Bookmark> {
+    if !self.supports(oauth, TweetAction::Bookmark) {
+      return Err(TwitterError::Unsupported.into());
+    }
+
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::DELETE,
+      &format!("users/{user_id}/bookmarks/{tweet_id}"),
+      None
+    ).await
+  }
+
+  async fn follow(self: &Self, oauth: &mut OAuthSession, target_user_id: &str) -> Result<Friendship> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::POST,
+      &format!("users/{user_id}/following"),
+      Some(json!({ "target_user_id": target_user_id }))
+    ).await
+  }
+
+  async fn unfollow(self: &Self, oauth: &mut OAuthSession, target_user_id: &str) -> Result<Friendship> {
+    let user_id: Arc<str> = self.get_user_id(oauth).await?;
+
+    self.send(
+      oauth,
+      Method::DELETE,
+      &format!("users/{user_id}/following/{target_user_id}"),
+      None
+    ).await
+  }
+
+  async fn get_tweet(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<Tweet> {
+    let tweet: V2Tweet = self.send(
+      oauth,
+      Method::GET,
+      &format!("tweets/{tweet_id}?expansions=author_id"),
+      None
+    ).await?;
+
+    Ok(
+      Tweet {
+        author_id: tweet.author_id.ok_or(anyhow!("Get author id failed."))?,
+        id: tweet.id,
+        text: tweet.text
+      }
+    )
+  }
+
+  async fn get_tweet_preview(self: &Self, oauth: &mut OAuthSession, tweet_id: &str) -> Result<TweetPreview> {
+    let (tweet, includes): (V2TweetPreview, V2Includes) = self.send_response(
+      oauth,
+      Method::GET,
+      &format!(
+        "tweets/{tweet_id}?expansions=author_id,attachments.media_keys&tweet.fields=public_metrics&user.fields=profile_image_url&media.fields=url,preview_image_url"
+      ),
+      None
+    ).await?;
+
+    tweet
+      .into_preview(includes)
+      .ok_or(anyhow!("Get tweet author failed."))
+  }
+
+  async fn get_me(self: &Self, oauth: &mut OAuthSession) -> Result<TwitterUser> {
+    Ok(
+      self.send::<V2User>(oauth, Method::GET, "users/me?user.fields=profile_image_url", None).await?.into()
+    )
+  }
+
+  async fn reply(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet> {
+    self.create_tweet(
+      oauth,
+      json!({ "text": text, "reply": { "in_reply_to_tweet_id": tweet_id } })
+    ).await
+  }
+
+  async fn quote(self: &Self, oauth: &mut OAuthSession, tweet_id: &str, text: &str) -> Result<Tweet> {
+    self.create_tweet(
+      oauth,
+      json!({ "text": text, "quote_tweet_id": tweet_id })
+    ).await
+  }
+
+  fn supports(self: &Self, oauth: &OAuthSession, action: TweetAction) -> bool {
+    (action != TweetAction::Bookmark || oauth.scheme() == AuthScheme::Bearer) && oauth.is_permitted(action)
+  }
+}
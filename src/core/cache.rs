@@ -1,120 +1,271 @@
-use std::{
-  sync::{Arc, OnceLock},
-  collections::HashMap,
-  time::{SystemTime, UNIX_EPOCH},
-};
+use std::sync::{OnceLock, Arc};
 
-use tokio::sync::{RwLock, RwLockWriteGuard};
+use tokio::sync::{Mutex, MutexGuard};
 use tracing::log::info;
+use anyhow::Result;
+
+use super::{
+  store::{TokenStore, SledTokenStore, StoredToken, StoredAccounts},
+  model::Tweet,
+  ttl::{TtlCache, CacheStats}
+};
 
 
 pub static MAX_AGE: u64 = 86400;
 pub static MAX_ITEM: usize = 1000;
 pub static ACCESS_TOKEN_CACHE: OnceLock<AccessTokenCache> = OnceLock::new();
 
+pub static TWEET_CACHE_MAX_AGE: u64 = 86400;
+pub static TWEET_CACHE_MAX_ITEM: usize = 10000;
+pub static TWEET_CACHE: OnceLock<TweetCache> = OnceLock::new();
+
 
 #[derive(Debug, Clone)]
 pub struct CacheData {
-  pub access_token: Arc<str>,
-  pub access_token_secret: Arc<str>,
-  cached_at: u64,
+  pub accounts: StoredAccounts
 }
 
 unsafe impl Send for CacheData {}
 
 impl CacheData {
-  fn new(
-    access_token: Arc<str>,
-    access_token_secret: Arc<str>
-  ) -> CacheData {
+  fn new(accounts: StoredAccounts) -> CacheData {
     CacheData {
-      access_token,
-      access_token_secret,
-      cached_at: SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Epoch fail!")
-        .as_secs(),
+      accounts
     }
   }
 }
 
 #[derive(Debug)]
 pub struct AccessTokenCache {
-  data: RwLock<HashMap<u64, CacheData>>,
-  max_age: u64
+  data: Mutex<TtlCache<u64, CacheData>>,
+  writer: Mutex<()>,
+  store: Box<dyn TokenStore>
 }
 
 unsafe impl Send for AccessTokenCache {}
 
 impl AccessTokenCache {
-  pub(self) fn new(max_age: u64, max_item: usize) -> AccessTokenCache {
+  pub(self) fn new(
+    store: Box<dyn TokenStore>,
+    max_age: u64,
+    max_item: usize
+  ) -> AccessTokenCache {
     AccessTokenCache {
-      data: RwLock::new(HashMap::with_capacity(max_item)),
-      max_age
+      data: Mutex::new(TtlCache::new(max_age, max_item)),
+      writer: Mutex::new(()),
+      store
     }
   }
 
   pub fn get() -> &'static AccessTokenCache {
     ACCESS_TOKEN_CACHE.get_or_init(
-      || AccessTokenCache::new(MAX_AGE, MAX_ITEM)
+      || {
+        AccessTokenCache::new(
+          Box::new(
+            SledTokenStore::from_env().expect("Token store init failed.")
+          ),
+          MAX_AGE,
+          MAX_ITEM
+        )
+      }
     )
   }
 
-  pub async fn request(self: &Self, user_id: u64) -> Option<CacheData> {
-    match self.data.read().await.get(&user_id) {
-      Some(cache_data) => Some(cache_data.clone()),
-      None => None
+  pub async fn request(self: &Self, user_id: u64) -> Result<Option<CacheData>> {
+    if let Some(cache_data) = self.data.lock().await.get(&user_id) {
+      return Ok(Some(cache_data.clone()));
     }
+
+    let _writer: MutexGuard<'_, ()> = self.writer.lock().await;
+
+    self.fetch(user_id).await
+  }
+
+  async fn fetch(self: &Self, user_id: u64) -> Result<Option<CacheData>> {
+    if let Some(cache_data) = self.data.lock().await.peek(&user_id) {
+      return Ok(Some(cache_data.clone()));
+    }
+
+    let accounts: StoredAccounts = match self.store.load(user_id)? {
+      Some(accounts) => accounts,
+      None => return Ok(None)
+    };
+
+    let cache_data: CacheData = CacheData::new(accounts);
+
+    self.data
+      .lock()
+      .await
+      .insert(user_id, cache_data.clone());
+
+    Ok(Some(cache_data))
   }
 
   pub async fn add(
     self: &Self,
     user_id: u64,
-    access_token: &str,
-    access_token_secret: &str
-  ) {
+    token: StoredToken
+  ) -> Result<()> {
+    self.update(
+      user_id,
+      |accounts: &mut StoredAccounts| accounts.insert(token)
+    ).await?;
+
+    Ok(())
+  }
+
+  pub async fn update<F: FnOnce(&mut StoredAccounts)>(
+    self: &Self,
+    user_id: u64,
+    modify: F
+  ) -> Result<StoredAccounts> {
+    let _writer: MutexGuard<'_, ()> = self.writer.lock().await;
+
+    let mut accounts: StoredAccounts = match self.fetch(user_id).await? {
+      Some(cache_data) => cache_data.accounts,
+      None => StoredAccounts::default()
+    };
+
+    modify(&mut accounts);
+
+    self.save(user_id, &accounts).await?;
+
+    Ok(accounts)
+  }
+
+  pub async fn remove_credential(
+    self: &Self,
+    user_id: u64,
+    access_token: &str
+  ) -> Result<Option<StoredToken>> {
+    let _writer: MutexGuard<'_, ()> = self.writer.lock().await;
+
+    let mut accounts: StoredAccounts = match self.fetch(user_id).await? {
+      Some(cache_data) => cache_data.accounts,
+      None => return Ok(None)
+    };
+
+    let token: StoredToken = match accounts.remove_credential(access_token) {
+      Some(token) => token,
+      None => return Ok(None)
+    };
+
+    match accounts.tokens.is_empty() {
+      true => self.discard(user_id).await?,
+      false => self.save(user_id, &accounts).await?
+    }
+
+    Ok(Some(token))
+  }
+
+  pub async fn load(self: &Self, user_id: u64) -> Result<Option<StoredAccounts>> {
+    if let Some(cache_data) = self.data.lock().await.peek(&user_id) {
+      return Ok(Some(cache_data.accounts.clone()));
+    }
+
+    self.store.load(user_id)
+  }
+
+  pub fn user_ids(self: &Self) -> Result<Vec<u64>> {
+    self.store.user_ids()
+  }
+
+  async fn save(self: &Self, user_id: u64, accounts: &StoredAccounts) -> Result<()> {
+    self.store.save(user_id, accounts)?;
+
     self.data
-      .write()
+      .lock()
       .await
       .insert(
         user_id,
-        CacheData::new(
-            access_token.into(),
-            access_token_secret.into()
-          )
+        CacheData::new(accounts.clone())
       );
+
+    Ok(())
+  }
+
+  pub async fn purge(self: &Self, user_id: u64) -> Result<()> {
+    let _writer: MutexGuard<'_, ()> = self.writer.lock().await;
+
+    self.discard(user_id).await
   }
 
-  pub async fn purge(self: &Self, user_id: u64) {
+  async fn discard(self: &Self, user_id: u64) -> Result<()> {
+    self.store.remove(user_id)?;
+
     self.data
-      .write()
+      .lock()
       .await
       .remove(&user_id);
+
+    Ok(())
   }
 
   pub async fn clean_up(self: &Self) {
-    let current_time: u64 = SystemTime::now()
-      .duration_since(UNIX_EPOCH)
-      .expect("Epoch fail!")
-      .as_secs();
-
-    let mut cache: RwLockWriteGuard<'_, HashMap<u64, CacheData>> = self.data.write().await;
-    let current_size: usize = cache.len();
+    let mut cache: MutexGuard<'_, TtlCache<u64, CacheData>> = self.data.lock().await;
 
     info!("Start cleaning cache...");
 
-    cache.retain(
-      |_, cache_data: &mut CacheData| {
-        (current_time - cache_data.cached_at) <= self.max_age
-      }
+    let released: usize = cache.clean_up();
+    let stats: CacheStats = cache.stats();
+
+    info!(
+      "Cache cleaned | Size: {size} | Released: {released} | Hits: {hits} | Misses: {misses} | Evictions: {evictions} | Expirations: {expirations}",
+      size = stats.size,
+      released = released,
+      hits = stats.hits,
+      misses = stats.misses,
+      evictions = stats.evictions,
+      expirations = stats.expirations
     );
-    cache.shrink_to(MAX_ITEM);
+  }
+}
+
+#[derive(Debug)]
+pub struct TweetCache {
+  data: Mutex<TtlCache<Arc<str>, Tweet>>
+}
+
+impl TweetCache {
+  pub fn get() -> &'static TweetCache {
+    TWEET_CACHE.get_or_init(
+      || {
+        TweetCache {
+          data: Mutex::new(TtlCache::new(TWEET_CACHE_MAX_AGE, TWEET_CACHE_MAX_ITEM))
+        }
+      }
+    )
+  }
+
+  pub async fn request(self: &Self, tweet_id: &str) -> Option<Tweet> {
+    self.data
+      .lock()
+      .await
+      .get(&Arc::from(tweet_id))
+      .cloned()
+  }
+
+  pub async fn add(self: &Self, tweet: Tweet) {
+    self.data
+      .lock()
+      .await
+      .insert(tweet.id.clone(), tweet);
+  }
+
+  pub async fn clean_up(self: &Self) {
+    let mut cache: MutexGuard<'_, TtlCache<Arc<str>, Tweet>> = self.data.lock().await;
+
+    let released: usize = cache.clean_up();
+    let stats: CacheStats = cache.stats();
 
     info!(
-      "Cache cleaned | Size: {size} | Capacity: {capacity} | Released: {released}",
-      size = cache.len(),
-      capacity = cache.capacity(),
-      released = current_size - cache.len()
+      "Tweet cache cleaned | Size: {size} | Released: {released} | Hits: {hits} | Misses: {misses} | Evictions: {evictions} | Expirations: {expirations}",
+      size = stats.size,
+      released = released,
+      hits = stats.hits,
+      misses = stats.misses,
+      evictions = stats.evictions,
+      expirations = stats.expirations
     );
   }
 }
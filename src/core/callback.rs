@@ -0,0 +1,186 @@
+use std::{
+  collections::HashMap,
+  convert::Infallible,
+  net::SocketAddr,
+  sync::{Arc, Mutex, OnceLock},
+  time::Duration,
+  env
+};
+
+use hyper::{
+  Server,
+  server::conn::AddrIncoming,
+  Body,
+  Request,
+  Response,
+  StatusCode,
+  header,
+  service::{make_service_fn, service_fn}
+};
+use tokio::{sync::oneshot, time::timeout};
+use tracing::log::{info, error, warn};
+use anyhow::{Result, anyhow};
+
+
+type CallbackSender = oneshot::Sender<Result<Arc<str>>>;
+
+
+pub static DEFAULT_CALLBACK_ADDRESS: &str = "0.0.0.0:8080";
+
+pub static OAUTH_CALLBACK_URL: OnceLock<Option<Arc<str>>> = OnceLock::new();
+
+static PENDING_CALLBACKS: OnceLock<Mutex<HashMap<Arc<str>, CallbackSender>>> = OnceLock::new();
+
+
+pub fn get_callback_url() -> Option<Arc<str>> {
+  OAUTH_CALLBACK_URL.get_or_init(
+    || env::var("OAUTH_CALLBACK_URL").ok().map(Arc::from)
+  ).clone()
+}
+
+fn get_pending_callbacks() -> &'static Mutex<HashMap<Arc<str>, CallbackSender>> {
+  PENDING_CALLBACKS.get_or_init(
+    || Mutex::new(HashMap::new())
+  )
+}
+
+
+#[derive(Debug)]
+pub struct PendingCallback {
+  key: Arc<str>,
+  receiver: oneshot::Receiver<Result<Arc<str>>>
+}
+
+impl PendingCallback {
+  pub fn register(key: Arc<str>) -> PendingCallback {
+    let (sender, receiver) = oneshot::channel();
+
+    get_pending_callbacks()
+      .lock()
+      .expect("Pending callbacks poisoned.")
+      .insert(key.clone(), sender);
+
+    PendingCallback {
+      key,
+      receiver
+    }
+  }
+
+  pub async fn wait(self: Self, duration: Duration) -> Option<Result<Arc<str>>> {
+    let result: Option<Result<Arc<str>>> = match timeout(duration, self.receiver).await {
+      Ok(Ok(result)) => Some(result),
+      Ok(Err(_)) | Err(_) => None
+    };
+
+    get_pending_callbacks()
+      .lock()
+      .expect("Pending callbacks poisoned.")
+      .remove(&self.key);
+
+    result
+  }
+}
+
+
+fn resolve_callback(query: &HashMap<String, String>) -> Option<(Arc<str>, Result<Arc<str>>)> {
+  if let (Some(oauth_token), Some(oauth_verifier)) = (query.get("oauth_token"), query.get("oauth_verifier")) {
+    return Some((oauth_token.as_str().into(), Ok(oauth_verifier.as_str().into())));
+  }
+
+  if let Some(denied) = query.get("denied") {
+    return Some((denied.as_str().into(), Err(anyhow!("Authorization denied."))));
+  }
+
+  match (query.get("state"), query.get("code"), query.get("error")) {
+    (Some(state), Some(code), _) => Some((state.as_str().into(), Ok(code.as_str().into()))),
+    (Some(state), None, Some(error)) => Some((state.as_str().into(), Err(anyhow!("Authorization failed: {}", error)))),
+    _ => None
+  }
+}
+
+fn handle(request: &Request<Body>) -> (StatusCode, &'static str) {
+  let query: HashMap<String, String> = request.uri()
+    .query()
+    .into_iter()
+    .flat_map(|query: &str| query.split("&"))
+    .filter_map(|pair: &str| pair.split_once("="))
+    .map(
+      |(k, v)| (
+        k.to_string(),
+        urlencoding::decode(v).map(|v| v.into_owned()).unwrap_or_default()
+      )
+    )
+    .collect();
+
+  let (key, result): (Arc<str>, Result<Arc<str>>) = match resolve_callback(&query) {
+    Some(callback) => callback,
+    None => return (StatusCode::BAD_REQUEST, "Invalid authorization callback.")
+  };
+
+  let sender: Option<CallbackSender> = get_pending_callbacks()
+    .lock()
+    .expect("Pending callbacks poisoned.")
+    .remove(&key);
+
+  let is_authorized: bool = result.is_ok();
+
+  match sender.map(|sender: CallbackSender| sender.send(result)) {
+    Some(Ok(())) if is_authorized => (StatusCode::OK, "Twitcord is connected, you can close this page and return to Discord."),
+    Some(Ok(())) => (StatusCode::OK, "Authorization cancelled, you can close this page and return to Discord."),
+    Some(Err(_)) | None => {
+      warn!("Unknown or expired authorization callback.");
+      (StatusCode::GONE, "Authorization expired, please use `/connect` again.")
+    }
+  }
+}
+
+
+#[derive(Debug)]
+pub struct CallbackServer {
+  address: SocketAddr
+}
+
+impl CallbackServer {
+  pub async fn start(address: SocketAddr) -> Result<CallbackServer> {
+    let incoming: AddrIncoming = AddrIncoming::bind(&address)?;
+    let address: SocketAddr = incoming.local_addr();
+
+    let server = Server::builder(incoming).serve(
+      make_service_fn(
+        |_| async {
+          Ok::<_, Infallible>(
+            service_fn(
+              |request: Request<Body>| async move {
+                let (status, message): (StatusCode, &str) = handle(&request);
+
+                info!("Authorization callback `{}` | Status: {}", request.uri().path(), status);
+
+                Ok::<_, Infallible>(
+                  Response::builder()
+                    .status(status)
+                    .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                    .body(Body::from(message))
+                    .unwrap_or_default()
+                )
+              }
+            )
+          )
+        }
+      )
+    );
+
+    tokio::spawn(
+      async move {
+        if let Err(why) = server.await {
+          error!("Callback server error: {:?}", why);
+        }
+      }
+    );
+
+    Ok(CallbackServer { address })
+  }
+
+  pub fn address(self: &Self) -> SocketAddr {
+    self.address
+  }
+}
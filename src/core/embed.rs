@@ -0,0 +1,87 @@
+use rust_i18n::t;
+use serenity::{
+  builder::{
+    CreateEmbed,
+    CreateEmbedAuthor,
+    CreateEmbedFooter
+  },
+  utils::Color
+};
+
+use super::model::TweetPreview;
+
+
+pub static TWEET_EMBED_COLOR: u32 = 0x1d9bf0;
+pub static MAX_EMBED_MEDIA: usize = 4;
+
+
+pub fn build_tweet_embeds(preview: &TweetPreview, locale: &str) -> Vec<CreateEmbed> {
+  let mut tweet_embed: CreateEmbed = CreateEmbed::default();
+  build_tweet_embed(&mut tweet_embed, preview, locale);
+
+  let mut embeds: Vec<CreateEmbed> = vec![tweet_embed];
+
+  for media_url in preview.media.iter().skip(1).take(MAX_EMBED_MEDIA - 1) {
+    let mut media_embed: CreateEmbed = CreateEmbed::default();
+    media_embed
+      .url(preview.url())
+      .image(media_url);
+
+    embeds.push(media_embed);
+  }
+
+  embeds
+}
+
+pub fn build_tweet_embed<'a>(
+  embed: &'a mut CreateEmbed,
+  preview: &TweetPreview,
+  locale: &str
+) -> &'a mut CreateEmbed {
+  embed
+    .color(Color::new(TWEET_EMBED_COLOR))
+    .url(preview.url())
+    .author(
+      |author: &mut CreateEmbedAuthor| {
+        author
+          .name(format!("{} (@{})", preview.author.name, preview.author.username))
+          .url(preview.author.url());
+
+        if let Some(profile_image_url) = &preview.author.profile_image_url {
+          author.icon_url(profile_image_url);
+        }
+
+        author
+      }
+    )
+    .description(unescape_text(&preview.text))
+    .field(
+      t!("core.embed.tweet.likes", locale = locale),
+      preview.like_count,
+      true
+    )
+    .field(
+      t!("core.embed.tweet.retweets", locale = locale),
+      preview.retweet_count,
+      true
+    )
+    .footer(
+      |footer: &mut CreateEmbedFooter| {
+        footer.text("Twitter")
+      }
+    );
+
+  if let Some(media_url) = preview.media.first() {
+    embed.image(media_url);
+  }
+
+  embed
+}
+
+
+fn unescape_text(text: &str) -> String {
+  text
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&amp;", "&")
+}
@@ -0,0 +1,221 @@
+use std::{
+  fmt::{Display, Formatter},
+  sync::Arc
+};
+
+use hyper::StatusCode;
+use rust_i18n::t;
+use serenity::{
+  model::{prelude::{Reaction, GuildId}, user::User},
+  prelude::Context,
+  builder::{
+    CreateEmbed,
+    CreateMessage,
+    CreateEmbedFooter
+  },
+  utils::Color
+};
+use tokio::time::error::Elapsed;
+use anyhow::Result;
+
+use super::{
+  cache::AccessTokenCache,
+  model::TwitterApiError,
+  store::{StoredAccounts, StoredToken},
+  utils::{EMBED_ERROR_COLOR, match_locale, get_guild_locale}
+};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwitterError {
+  NotConnected,
+  Unauthorized,
+  Revoked,
+  RateLimited { reset_at: Option<u64> },
+  TweetNotFound,
+  Protected,
+  AlreadyDone,
+  AppSuspended,
+  Unsupported,
+  Network,
+  Unknown
+}
+
+impl Display for TwitterError {
+  fn fmt(self: &Self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(self.code())
+  }
+}
+
+impl std::error::Error for TwitterError {}
+
+impl From<&TwitterApiError> for TwitterError {
+  fn from(error: &TwitterApiError) -> TwitterError {
+    match (error.status, error.code) {
+      (_, Some(89)) => TwitterError::Revoked,
+      (_, Some(32 | 215)) | (StatusCode::UNAUTHORIZED, _) => TwitterError::Unauthorized,
+      (_, Some(88)) | (StatusCode::TOO_MANY_REQUESTS, _) => TwitterError::RateLimited {
+        reset_at: error.rate_limit_reset
+      },
+      (_, Some(8 | 34 | 50 | 63 | 144 | 385 | 421 | 422)) | (StatusCode::NOT_FOUND, _) => TwitterError::TweetNotFound,
+      (_, Some(139 | 160 | 187 | 327)) => TwitterError::AlreadyDone,
+      (_, Some(179 | 433)) => TwitterError::Protected,
+      (_, Some(261 | 326 | 416)) => TwitterError::AppSuspended,
+      (StatusCode::FORBIDDEN, _) => match error.problem_type.as_deref() {
+        Some("https://api.twitter.com/2/problems/client-forbidden") => TwitterError::AppSuspended,
+        _ => TwitterError::Protected
+      },
+      (status, _) if status.is_server_error() => TwitterError::Network,
+      _ => TwitterError::Unknown
+    }
+  }
+}
+
+impl TwitterError {
+  pub fn classify(error: &anyhow::Error) -> TwitterError {
+    if let Some(error) = error.downcast_ref::<TwitterError>() {
+      return *error;
+    }
+
+    if let Some(error) = error.downcast_ref::<TwitterApiError>() {
+      return error.into();
+    }
+
+    if error.is::<hyper::Error>() || error.is::<std::io::Error>() || error.is::<Elapsed>() {
+      return TwitterError::Network;
+    }
+
+    TwitterError::Unknown
+  }
+
+  pub fn code(self: &Self) -> &'static str {
+    match self {
+      TwitterError::NotConnected => "ERR_ACCESS_TOKEN_NOT_FOUND",
+      TwitterError::Unauthorized => "ERR_UNAUTHORIZED",
+      TwitterError::Revoked => "ERR_TOKEN_REVOKED",
+      TwitterError::RateLimited { .. } => "ERR_RATE_LIMITED",
+      TwitterError::TweetNotFound => "ERR_TWEET_NOT_FOUND",
+      TwitterError::Protected => "ERR_PROTECTED",
+      TwitterError::AlreadyDone => "ERR_ALREADY_DONE",
+      TwitterError::AppSuspended => "ERR_APP_SUSPENDED",
+      TwitterError::Unsupported => "ERR_UNSUPPORTED",
+      TwitterError::Network => "ERR_NETWORK",
+      TwitterError::Unknown => "ERR_UNKNOWN"
+    }
+  }
+
+  pub fn build_embed<'a>(
+    self: &Self,
+    embed: &'a mut CreateEmbed,
+    locale: &str
+  ) -> &'a mut CreateEmbed {
+    let (title, description): (String, String) = match self {
+      TwitterError::NotConnected => (
+        t!("core.oauth.get-client.notify-embed.title", locale = locale),
+        t!("core.oauth.get-client.notify-embed.description", locale = locale)
+      ),
+      TwitterError::Unauthorized => (
+        t!("core.error.unauthorized-embed.title", locale = locale),
+        t!("core.error.unauthorized-embed.description", locale = locale)
+      ),
+      TwitterError::Revoked => (
+        t!("core.expiry.expired-embed.title", locale = locale),
+        t!("core.expiry.expired-embed.description-unknown", locale = locale)
+      ),
+      TwitterError::RateLimited { reset_at: Some(reset_at) } => (
+        t!("core.error.rate-limited-embed.title", locale = locale),
+        t!("core.error.rate-limited-embed.description", locale = locale, reset_at = reset_at)
+      ),
+      TwitterError::RateLimited { reset_at: None } => (
+        t!("core.error.rate-limited-embed.title", locale = locale),
+        t!("core.error.rate-limited-embed.description-unknown", locale = locale)
+      ),
+      TwitterError::TweetNotFound => (
+        t!("core.error.tweet-not-found-embed.title", locale = locale),
+        t!("core.error.tweet-not-found-embed.description", locale = locale)
+      ),
+      TwitterError::Protected => (
+        t!("core.error.protected-embed.title", locale = locale),
+        t!("core.error.protected-embed.description", locale = locale)
+      ),
+      TwitterError::AlreadyDone => (
+        t!("core.error.already-done-embed.title", locale = locale),
+        t!("core.error.already-done-embed.description", locale = locale)
+      ),
+      TwitterError::AppSuspended => (
+        t!("core.error.app-suspended-embed.title", locale = locale),
+        t!("core.error.app-suspended-embed.description", locale = locale)
+      ),
+      TwitterError::Unsupported => (
+        t!("core.error.unsupported-embed.title", locale = locale),
+        t!("core.error.unsupported-embed.description", locale = locale)
+      ),
+      TwitterError::Network => (
+        t!("core.error.network-embed.title", locale = locale),
+        t!("core.error.network-embed.description", locale = locale)
+      ),
+      TwitterError::Unknown => (
+        t!("core.error.unknown-embed.title", locale = locale),
+        t!("core.error.unknown-embed.description", locale = locale)
+      )
+    };
+
+    embed
+      .color(Color::new(EMBED_ERROR_COLOR))
+      .title(title)
+      .description(description)
+      .footer(
+        |footer: &mut CreateEmbedFooter| {
+          footer.text(self.code())
+        }
+      )
+  }
+
+  pub async fn notify(
+    self: &Self,
+    context: &Context,
+    user: &User,
+    locale: &str
+  ) -> Result<()> {
+    user.direct_message(
+      &context.http,
+      |message: &mut CreateMessage<'_>| {
+        message
+          .embed(
+            |embed: &mut CreateEmbed| {
+              self.build_embed(embed, locale)
+            }
+          )
+      }
+    ).await?;
+
+    Ok(())
+  }
+
+  pub async fn notify_reaction(
+    self: &Self,
+    context: &Context,
+    reaction: &Reaction
+  ) -> Result<()> {
+    let user: User = reaction.user(&context.http).await?;
+
+    let stored_locale: Option<Arc<str>> = AccessTokenCache::get()
+      .load(*user.id.as_u64())
+      .await
+      .ok()
+      .flatten()
+      .and_then(
+        |accounts: StoredAccounts| accounts
+          .select(reaction.guild_id.map(|guild_id: GuildId| *guild_id.as_u64()))
+          .map(|token: &StoredToken| token.locale.clone())
+      )
+      .filter(|locale: &Arc<str>| !locale.is_empty());
+
+    let locale: String = match stored_locale {
+      Some(locale) => match_locale(&locale),
+      None => get_guild_locale(context, reaction.guild_id).await
+    };
+
+    self.notify(context, &user, &locale).await
+  }
+}
@@ -0,0 +1,197 @@
+use std::{
+  env,
+  sync::{Arc, Mutex, MutexGuard, OnceLock},
+  collections::HashMap,
+  time::Duration
+};
+
+use rust_i18n::t;
+use serenity::{
+  http::Http,
+  model::prelude::{PrivateChannel, UserId},
+  builder::{CreateMessage, CreateEmbed, CreateEmbedFooter},
+  utils::Color
+};
+use hyper::StatusCode;
+use tokio::time::sleep;
+use tracing::log::{info, warn, error};
+use anyhow::{Result, anyhow};
+
+use super::{
+  api::forget_user_id,
+  cache::AccessTokenCache,
+  error::TwitterError,
+  model::TwitterApiError,
+  oauth::{TwitterClient, AuthScheme},
+  store::{StoredAccounts, StoredToken},
+  utils::{EMBED_ERROR_COLOR, match_locale, current_time}
+};
+
+
+pub static EXPIRY_NOTICE_COOLDOWN: u64 = 86400;
+pub static DEFAULT_TOKEN_VALIDATION_INTERVAL: u64 = 43200;
+
+pub static DISCORD_HTTP: OnceLock<Arc<Http>> = OnceLock::new();
+
+static TOKEN_VALIDATION_DELAY: Duration = Duration::from_secs(1);
+static TOKEN_VALIDATION_INTERVAL: OnceLock<u64> = OnceLock::new();
+static EXPIRY_NOTICES: OnceLock<Mutex<HashMap<u64, u64>>> = OnceLock::new();
+
+
+pub fn get_token_validation_interval() -> u64 {
+  *TOKEN_VALIDATION_INTERVAL.get_or_init(
+    || {
+      env::var("TOKEN_VALIDATION_INTERVAL")
+        .ok()
+        .map(|interval: String| interval.parse().expect("Invalid TOKEN_VALIDATION_INTERVAL."))
+        .unwrap_or(DEFAULT_TOKEN_VALIDATION_INTERVAL)
+    }
+  )
+}
+
+pub fn is_revoked(error: &anyhow::Error, scheme: AuthScheme) -> bool {
+  match error.downcast_ref::<TwitterApiError>() {
+    Some(error) => match (error.status, error.code) {
+      (_, Some(89)) => true,
+      (StatusCode::UNAUTHORIZED, None) => scheme == AuthScheme::Bearer,
+      _ => false
+    },
+    None => false
+  }
+}
+
+pub fn is_grant_revoked(error: &anyhow::Error) -> bool {
+  match error.downcast_ref::<TwitterApiError>() {
+    Some(error) => match (error.status, error.oauth_error.as_deref()) {
+      (_, Some("invalid_grant")) => true,
+      (_, Some("invalid_request")) => error.message.contains("token was invalid"),
+      (_, Some("invalid_client" | "unauthorized_client")) => false,
+      (status, _) => status == StatusCode::UNAUTHORIZED
+    },
+    None => false
+  }
+}
+
+pub async fn expire_credential(user_id: u64, access_token: &str) {
+  forget_user_id(access_token).await;
+
+  let token: StoredToken = match AccessTokenCache::get().remove_credential(user_id, access_token).await {
+    Ok(Some(token)) => token,
+    Ok(None) => return,
+    Err(why) => {
+      error!("Purge expired credential failed: {:?} | User: {}", why, user_id);
+      return;
+    }
+  };
+
+  info!("Purged expired credential | User: {} | Account: {}", user_id, token.account_id);
+
+  if !take_notice_slot(user_id) {
+    return;
+  }
+
+  if let Err(why) = send_expiry_notice(user_id, &token).await {
+    warn!("Send expiry notice failed: {:?} | User: {}", why, user_id);
+  }
+}
+
+pub async fn validate_tokens() {
+  let user_ids: Vec<u64> = match AccessTokenCache::get().user_ids() {
+    Ok(user_ids) => user_ids,
+    Err(why) => {
+      error!("List stored tokens failed: {:?}", why);
+      return;
+    }
+  };
+
+  info!("Start validating tokens... | Users: {}", user_ids.len());
+
+  let mut expired: usize = 0;
+
+  for user_id in user_ids {
+    let accounts: StoredAccounts = match AccessTokenCache::get().load(user_id).await {
+      Ok(Some(accounts)) => accounts,
+      Ok(None) => continue,
+      Err(why) => {
+        warn!("Load stored tokens failed: {:?} | User: {}", why, user_id);
+        continue;
+      }
+    };
+
+    for token in accounts.tokens {
+      sleep(TOKEN_VALIDATION_DELAY).await;
+
+      let result: Result<()> = match TwitterClient::from_token(user_id, &token).await {
+        Ok(mut twitter_client) => twitter_client.get_me().await.map(|_| ()),
+        Err(why) => Err(why)
+      };
+
+      if let Err(why) = result {
+        match TwitterError::classify(&why) {
+          TwitterError::Revoked => expired += 1,
+          error => warn!("Validate token failed: {} | User: {} | Account: {}", error, user_id, token.account_id)
+        }
+      }
+    }
+  }
+
+  info!("Tokens validated | Expired: {}", expired);
+}
+
+
+fn take_notice_slot(user_id: u64) -> bool {
+  let current_time: u64 = current_time();
+
+  let mut notices: MutexGuard<HashMap<u64, u64>> = expiry_notices();
+  notices.retain(|_, notified_at: &mut u64| current_time.saturating_sub(*notified_at) <= EXPIRY_NOTICE_COOLDOWN);
+
+  match notices.contains_key(&user_id) {
+    true => false,
+    false => {
+      notices.insert(user_id, current_time);
+      true
+    }
+  }
+}
+
+async fn send_expiry_notice(user_id: u64, token: &StoredToken) -> Result<()> {
+  let http: &Arc<Http> = DISCORD_HTTP
+    .get()
+    .ok_or(anyhow!("Discord HTTP client is not ready."))?;
+
+  let dm_channel: PrivateChannel = UserId(user_id).create_dm_channel(http).await?;
+
+  let locale: String = match_locale(&token.locale);
+  let description: String = match token.is_identified() {
+    true => t!("core.expiry.expired-embed.description", locale = &locale, username = token.screen_name),
+    false => t!("core.expiry.expired-embed.description-unknown", locale = &locale)
+  };
+
+  dm_channel.send_message(
+    http,
+    |message: &mut CreateMessage<'_>| {
+      message.embed(
+        |embed: &mut CreateEmbed| {
+          embed
+            .color(Color::new(EMBED_ERROR_COLOR))
+            .title(t!("core.expiry.expired-embed.title", locale = &locale))
+            .description(description)
+            .footer(
+              |footer: &mut CreateEmbedFooter| {
+                footer.text(TwitterError::Revoked.code())
+              }
+            )
+        }
+      )
+    }
+  ).await?;
+
+  Ok(())
+}
+
+fn expiry_notices() -> MutexGuard<'static, HashMap<u64, u64>> {
+  EXPIRY_NOTICES
+    .get_or_init(|| Mutex::new(HashMap::new()))
+    .lock()
+    .expect("Expiry notices poisoned.")
+}
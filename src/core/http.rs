@@ -0,0 +1,100 @@
+use std::{
+  io::Read,
+  time::Duration
+};
+
+use brotli_decompressor::Decompressor;
+use flate2::read::{GzDecoder, ZlibDecoder, DeflateDecoder};
+use hyper::{
+  Body,
+  Response,
+  Method,
+  StatusCode,
+  header,
+  body::HttpBody
+};
+use rand::Rng;
+use anyhow::{Result, anyhow, bail};
+
+
+pub static MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+pub static MAX_RETRIES: u32 = 3;
+pub static REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+pub static ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+static RETRY_BASE_DELAY: u64 = 250;
+static RETRY_MAX_DELAY: u64 = 5000;
+static BROTLI_BUFFER_SIZE: usize = 4096;
+
+
+pub fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+  )
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+  matches!(
+    status,
+    StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+  )
+}
+
+pub fn retry_delay(attempt: u32) -> Duration {
+  let ceiling: u64 = RETRY_MAX_DELAY.min(RETRY_BASE_DELAY << attempt.min(16));
+
+  Duration::from_millis(
+    rand::thread_rng().gen_range(ceiling / 2..=ceiling)
+  )
+}
+
+
+fn read_capped<R: Read>(reader: R) -> Result<String> {
+  let mut decoded: Vec<u8> = Vec::new();
+
+  reader
+    .take(MAX_BODY_SIZE as u64 + 1)
+    .read_to_end(&mut decoded)?;
+
+  if decoded.len() > MAX_BODY_SIZE {
+    bail!("Response body exceeds {} bytes.", MAX_BODY_SIZE);
+  }
+
+  Ok(String::from_utf8(decoded)?)
+}
+
+fn decode_body(encoding: &str, raw_body: &[u8]) -> Result<String> {
+  match encoding {
+    "" | "identity" => read_capped(raw_body),
+    "gzip" | "x-gzip" => read_capped(GzDecoder::new(raw_body)),
+    "deflate" => match read_capped(ZlibDecoder::new(raw_body)) {
+      Ok(body) => Ok(body),
+      Err(_) => read_capped(DeflateDecoder::new(raw_body))
+    },
+    "br" => read_capped(Decompressor::new(raw_body, BROTLI_BUFFER_SIZE)),
+    encoding => Err(anyhow!("Unsupported Content-Encoding `{}`.", encoding))
+  }
+}
+
+pub async fn read_body(response: Response<Body>) -> Result<String> {
+  let encoding: String = response.headers()
+    .get(header::CONTENT_ENCODING)
+    .and_then(|encoding: &header::HeaderValue| encoding.to_str().ok())
+    .unwrap_or("")
+    .trim()
+    .to_ascii_lowercase();
+
+  let mut body: Body = response.into_body();
+  let mut raw_body: Vec<u8> = Vec::new();
+
+  while let Some(chunk) = body.data().await {
+    raw_body.extend_from_slice(&chunk?);
+
+    if raw_body.len() > MAX_BODY_SIZE {
+      bail!("Response body exceeds {} bytes.", MAX_BODY_SIZE);
+    }
+  }
+
+  decode_body(&encoding, &raw_body)
+}
@@ -0,0 +1,303 @@
+use std::{
+  env,
+  sync::{Arc, Mutex, MutexGuard, OnceLock}
+};
+
+use hyper::{
+  Body,
+  Client,
+  Method,
+  Request,
+  Response,
+  header,
+  client::HttpConnector
+};
+use hyper_rustls::{
+  HttpsConnector as rustls_HttpsConnector,
+  HttpsConnectorBuilder
+};
+use itertools::Itertools;
+use regex::{Regex, Captures};
+use tokio::time::timeout;
+use tracing::log::warn;
+use anyhow::{Result, anyhow};
+
+use super::{
+  http::REQUEST_TIMEOUT,
+  markdown::{Token, TokenKind, tokenize},
+  ttl::TtlCache
+};
+
+
+type HttpsConnector = rustls_HttpsConnector<HttpConnector>;
+type ShortLinkCache = TtlCache<Arc<str>, Option<Arc<str>>>;
+
+
+pub static DEFAULT_TWEET_DOMAINS: [&str; 7] = [
+  "twitter.com",
+  "x.com",
+  "fxtwitter.com",
+  "vxtwitter.com",
+  "fixupx.com",
+  "fixvx.com",
+  "twittpr.com"
+];
+
+pub static TWEET_ID_MAX_LENGTH: usize = 19;
+pub static SHORT_LINK_CACHE_MAX_AGE: u64 = 86400;
+pub static SHORT_LINK_CACHE_MAX_ITEM: usize = 10000;
+
+static TWEET_DOMAINS: OnceLock<Vec<Arc<str>>> = OnceLock::new();
+static TWEET_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+static RESOLVE_SHORT_LINKS: OnceLock<bool> = OnceLock::new();
+static SHORT_LINK_CACHE: OnceLock<Mutex<ShortLinkCache>> = OnceLock::new();
+static HTTP_CLIENT: OnceLock<Client<HttpsConnector, Body>> = OnceLock::new();
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkScope {
+  pub quotes: bool,
+  pub spoilers: bool
+}
+
+impl LinkScope {
+  pub fn contains(self: &Self, token: &Token<'_>) -> bool {
+    token.kind == TokenKind::Text
+      && (self.quotes || !token.quoted)
+      && (self.spoilers || !token.spoiler)
+  }
+
+  pub fn filter(self: &Self, message: &str) -> String {
+    tokenize(message)
+      .iter()
+      .filter(|token: &&Token<'_>| self.contains(token))
+      .map(|token: &Token<'_>| token.text)
+      .join("\n")
+  }
+}
+
+
+pub fn get_tweet_domains() -> &'static [Arc<str>] {
+  TWEET_DOMAINS.get_or_init(
+    || {
+      let extra_domains: String = env::var("TWEET_LINK_DOMAINS").unwrap_or_default();
+
+      DEFAULT_TWEET_DOMAINS
+        .into_iter()
+        .chain(extra_domains.split(','))
+        .map(|domain: &str| domain.trim().trim_start_matches("www.").to_ascii_lowercase())
+        .filter(|domain: &String| !domain.is_empty())
+        .unique()
+        .map(Arc::from)
+        .collect()
+    }
+  )
+}
+
+pub fn is_short_link_resolution_enabled() -> bool {
+  *RESOLVE_SHORT_LINKS.get_or_init(
+    || {
+      matches!(
+        env::var("RESOLVE_SHORT_LINKS").as_deref(),
+        Ok("1") | Ok("true")
+      )
+    }
+  )
+}
+
+pub fn validate_tweet_id(tweet_id: &str) -> bool {
+  !tweet_id.is_empty()
+    && tweet_id.len() <= TWEET_ID_MAX_LENGTH
+    && tweet_id.bytes().all(|byte: u8| byte.is_ascii_digit())
+    && tweet_id.parse::<u64>().is_ok_and(|tweet_id: u64| tweet_id > 0)
+}
+
+pub fn parse_tweet_id(url: &str) -> Option<Arc<str>> {
+  get_tweet_link_regex()
+    .captures_iter(url)
+    .find_map(|captures: Captures<'_>| captures_tweet_id(&captures))
+}
+
+pub fn parse_tweet_ids(message: &str) -> Vec<Arc<str>> {
+  get_tweet_link_regex()
+    .captures_iter(message)
+    .filter_map(|captures: Captures<'_>| captures_tweet_id(&captures))
+    .unique()
+    .collect()
+}
+
+pub async fn resolve_tweet_ids(message: &str) -> Vec<Arc<str>> {
+  if !is_short_link_resolution_enabled() {
+    return parse_tweet_ids(message);
+  }
+
+  let mut tweet_ids: Vec<Arc<str>> = Vec::new();
+
+  for captures in get_tweet_link_regex().captures_iter(message) {
+    let tweet_id: Option<Arc<str>> = match captures.name("short_link") {
+      Some(short_link) => resolve_short_link(
+        &short_link.as_str().replacen("http://", "https://", 1)
+      ).await,
+      None => captures_tweet_id(&captures)
+    };
+
+    if let Some(tweet_id) = tweet_id {
+      if !tweet_ids.contains(&tweet_id) {
+        tweet_ids.push(tweet_id);
+      }
+    }
+  }
+
+  tweet_ids
+}
+
+pub async fn resolve_short_link(short_link: &str) -> Option<Arc<str>> {
+  if let Some(tweet_id) = get_cached_short_link(short_link) {
+    return tweet_id;
+  }
+
+  let tweet_id: Option<Arc<str>> = match fetch_location(short_link).await {
+    Ok(location) => parse_tweet_id(&location),
+    Err(why) => {
+      warn!("Resolve short link error: {:?} | Link: {}", why, short_link);
+      return None;
+    }
+  };
+
+  put_cached_short_link(short_link.into(), tweet_id.clone());
+
+  tweet_id
+}
+
+
+fn get_tweet_link_regex() -> &'static Regex {
+  TWEET_LINK_REGEX.get_or_init(
+    || {
+      Regex::new(
+        &format!(
+          r#"(?i:https?://(?:www\.|mobile\.)?(?:{domains}))/(?:i/web/|[A-Za-z0-9_]{{1,15}}/)?status(?:es)?/(?P<tweet_id>[0-9]+)|(?P<short_link>(?i:https?://t\.co)/[A-Za-z0-9]+)"#,
+          domains = get_tweet_domains()
+            .iter()
+            .map(|domain: &Arc<str>| regex::escape(domain))
+            .join("|")
+        )
+      ).expect("Regex init failed.")
+    }
+  )
+}
+
+fn captures_tweet_id(captures: &Captures<'_>) -> Option<Arc<str>> {
+  let tweet_id: &str = captures.name("tweet_id")?.as_str();
+
+  match validate_tweet_id(tweet_id) {
+    true => Some(tweet_id.into()),
+    false => None
+  }
+}
+
+fn get_cached_short_link(short_link: &str) -> Option<Option<Arc<str>>> {
+  short_link_cache()
+    .get(&Arc::from(short_link))
+    .cloned()
+}
+
+fn put_cached_short_link(short_link: Arc<str>, tweet_id: Option<Arc<str>>) {
+  short_link_cache().insert(short_link, tweet_id);
+}
+
+fn short_link_cache() -> MutexGuard<'static, ShortLinkCache> {
+  SHORT_LINK_CACHE
+    .get_or_init(|| Mutex::new(TtlCache::new(SHORT_LINK_CACHE_MAX_AGE, SHORT_LINK_CACHE_MAX_ITEM)))
+    .lock()
+    .expect("Short link cache poisoned.")
+}
+
+async fn fetch_location(url: &str) -> Result<Arc<str>> {
+  let request: Request<Body> = Request::builder()
+    .method(Method::HEAD)
+    .uri(url)
+    .body(Body::empty())?;
+
+  let response: Response<Body> = timeout(
+    REQUEST_TIMEOUT,
+    get_http_client().request(request)
+  ).await??;
+
+  if !response.status().is_redirection() {
+    return Err(anyhow!("Unexpected status `{}`.", response.status()));
+  }
+
+  Ok(
+    response
+      .headers()
+      .get(header::LOCATION)
+      .ok_or(anyhow!("Get location header failed."))?
+      .to_str()?
+      .into()
+  )
+}
+
+fn get_http_client() -> Client<HttpsConnector, Body> {
+  HTTP_CLIENT.get_or_init(
+    || {
+      Client::builder().build(
+        HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .https_or_http()
+        .enable_http1()
+        .build()
+      )
+    }
+  ).clone()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use super::{DEFAULT_TWEET_DOMAINS, parse_tweet_id, parse_tweet_ids, validate_tweet_id};
+
+
+  #[test]
+  fn parses_default_domains() {
+    for domain in DEFAULT_TWEET_DOMAINS {
+      for url in [
+        format!("https://{domain}/user/status/1234567890"),
+        format!("http://www.{domain}/user/statuses/1234567890?s=20"),
+        format!("https://mobile.{domain}/i/web/status/1234567890"),
+        format!("https://{}/status/1234567890", domain.to_ascii_uppercase())
+      ] {
+        assert_eq!(parse_tweet_id(&url).as_deref(), Some("1234567890"), "{url}");
+      }
+    }
+  }
+
+  #[test]
+  fn ignores_lookalike_domains() {
+    assert_eq!(parse_tweet_id("https://nottwitter.com/user/status/1"), None);
+    assert_eq!(parse_tweet_id("https://twitter.com.example.com/user/status/1"), None);
+    assert_eq!(parse_tweet_id("https://twitter.com/user/likes/1"), None);
+  }
+
+  #[test]
+  fn validates_tweet_ids() {
+    assert!(validate_tweet_id("1"));
+    assert!(validate_tweet_id("9999999999999999999"));
+    assert!(!validate_tweet_id(""));
+    assert!(!validate_tweet_id("0"));
+    assert!(!validate_tweet_id("0000"));
+    assert!(!validate_tweet_id("10000000000000000000"));
+    assert!(!validate_tweet_id("18446744073709551616"));
+    assert!(!validate_tweet_id("12a"));
+    assert!(!validate_tweet_id("-1"));
+  }
+
+  #[test]
+  fn parses_valid_unique_ids() {
+    let tweet_ids: Vec<Arc<str>> = parse_tweet_ids(
+      "https://x.com/a/status/0 https://x.com/a/status/18446744073709551616 https://x.com/a/status/2 https://twitter.com/b/status/2 https://fxtwitter.com/c/status/3"
+    );
+
+    assert_eq!(tweet_ids, vec![Arc::from("2"), Arc::from("3")]);
+  }
+}
@@ -0,0 +1,104 @@
+use std::{
+  sync::{Arc, Mutex, MutexGuard},
+  collections::HashMap,
+  hash::Hash
+};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+
+type LockMap<K> = HashMap<K, Arc<AsyncMutex<()>>>;
+
+
+#[derive(Debug, Default)]
+pub struct KeyedLock<K: Eq + Hash + Clone> {
+  locks: Mutex<LockMap<K>>
+}
+
+impl<K: Eq + Hash + Clone> KeyedLock<K> {
+  pub fn new() -> KeyedLock<K> {
+    KeyedLock {
+      locks: Mutex::new(HashMap::new())
+    }
+  }
+
+  pub async fn lock(self: &Self, key: K) -> KeyedGuard<'_, K> {
+    let lock: Arc<AsyncMutex<()>> = self.locks()
+      .entry(key.clone())
+      .or_default()
+      .clone();
+
+    KeyedGuard {
+      owner: self,
+      key,
+      guard: Some(lock.lock_owned().await)
+    }
+  }
+
+  fn locks(self: &Self) -> MutexGuard<'_, LockMap<K>> {
+    self.locks
+      .lock()
+      .expect("Keyed locks poisoned.")
+  }
+}
+
+
+pub struct KeyedGuard<'a, K: Eq + Hash + Clone> {
+  owner: &'a KeyedLock<K>,
+  key: K,
+  guard: Option<OwnedMutexGuard<()>>
+}
+
+impl<K: Eq + Hash + Clone> Drop for KeyedGuard<'_, K> {
+  fn drop(self: &mut Self) {
+    let mut locks: MutexGuard<'_, LockMap<K>> = self.owner.locks();
+
+    self.guard.take();
+
+    if locks.get(&self.key).is_some_and(|lock: &Arc<AsyncMutex<()>>| Arc::strong_count(lock) == 1) {
+      locks.remove(&self.key);
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::{sync::Arc, time::Duration};
+
+  use tokio::{sync::Mutex, time::sleep};
+
+  use super::{KeyedLock, KeyedGuard};
+
+
+  #[tokio::test]
+  async fn serializes_same_key_and_releases_entries() {
+    let keyed_lock: Arc<KeyedLock<u64>> = Arc::new(KeyedLock::new());
+    let events: Arc<Mutex<Vec<&str>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let first: KeyedGuard<'_, u64> = keyed_lock.lock(1).await;
+
+    let waiter: tokio::task::JoinHandle<()> = tokio::spawn(
+      {
+        let keyed_lock: Arc<KeyedLock<u64>> = keyed_lock.clone();
+        let events: Arc<Mutex<Vec<&str>>> = events.clone();
+
+        async move {
+          let _second: KeyedGuard<'_, u64> = keyed_lock.lock(1).await;
+          events.lock().await.push("second");
+        }
+      }
+    );
+
+    let other: KeyedGuard<'_, u64> = keyed_lock.lock(2).await;
+    sleep(Duration::from_millis(20)).await;
+    events.lock().await.push("first");
+    drop(first);
+
+    waiter.await.expect("Waiter panicked.");
+    drop(other);
+
+    assert_eq!(*events.lock().await, vec!["first", "second"]);
+    assert!(keyed_lock.locks().is_empty());
+  }
+}
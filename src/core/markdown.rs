@@ -0,0 +1,231 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+  Text,
+  InlineCode,
+  CodeBlock
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+  pub kind: TokenKind,
+  pub text: &'a str,
+  pub quoted: bool,
+  pub spoiler: bool
+}
+
+
+struct Tokenizer<'a> {
+  message: &'a str,
+  tokens: Vec<Token<'a>>,
+  start: usize,
+  index: usize,
+  quoted: bool,
+  block_quoted: bool,
+  spoiler: bool
+}
+
+impl<'a> Tokenizer<'a> {
+  fn new(message: &'a str) -> Tokenizer<'a> {
+    Tokenizer {
+      message,
+      tokens: Vec::new(),
+      start: 0,
+      index: 0,
+      quoted: false,
+      block_quoted: false,
+      spoiler: false
+    }
+  }
+
+  fn run(mut self: Self) -> Vec<Token<'a>> {
+    while self.index < self.message.len() {
+      let rest: &'a str = &self.message[self.index..];
+
+      if self.is_line_start() && !self.block_quoted {
+        if rest.starts_with(">>> ") {
+          self.flush();
+          self.quoted = true;
+          self.block_quoted = true;
+          self.skip(4);
+          continue;
+        }
+
+        if rest.starts_with("> ") {
+          self.flush();
+          self.quoted = true;
+          self.skip(2);
+          continue;
+        }
+      }
+
+      if let Some(escaped) = rest.strip_prefix('\\') {
+        self.index += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+      } else if rest.starts_with('\n') && self.quoted && !self.block_quoted {
+        self.index += 1;
+        self.flush();
+        self.quoted = false;
+      } else if rest.starts_with('`') {
+        self.code(rest);
+      } else if let Some(remaining) = rest.strip_prefix("||") {
+        if self.spoiler || remaining.contains("||") {
+          self.flush();
+          self.spoiler = !self.spoiler;
+          self.skip(2);
+        } else {
+          self.index += 2;
+        }
+      } else {
+        self.index += rest.chars().next().map_or(1, char::len_utf8);
+      }
+    }
+
+    self.flush();
+    self.tokens
+  }
+
+  fn code(self: &mut Self, rest: &'a str) {
+    let (fence, kind): (&str, TokenKind) = if rest.starts_with("```") {
+      ("```", TokenKind::CodeBlock)
+    } else if rest.starts_with("``") {
+      ("``", TokenKind::InlineCode)
+    } else {
+      ("`", TokenKind::InlineCode)
+    };
+
+    let content: &'a str = &rest[fence.len()..];
+
+    match content.find(fence) {
+      Some(end) if end > 0 => {
+        self.flush();
+        self.push(kind, &content[..end]);
+        self.skip(fence.len() * 2 + end);
+      },
+      _ => self.index += fence.len()
+    }
+  }
+
+  fn is_line_start(self: &Self) -> bool {
+    self.index == 0 || self.message.as_bytes()[self.index - 1] == b'\n'
+  }
+
+  fn skip(self: &mut Self, length: usize) {
+    self.index += length;
+    self.start = self.index;
+  }
+
+  fn flush(self: &mut Self) {
+    if self.start < self.index {
+      self.push(TokenKind::Text, &self.message[self.start..self.index]);
+    }
+
+    self.start = self.index;
+  }
+
+  fn push(self: &mut Self, kind: TokenKind, text: &'a str) {
+    self.tokens.push(
+      Token {
+        kind,
+        text,
+        quoted: self.quoted,
+        spoiler: self.spoiler
+      }
+    );
+  }
+}
+
+
+pub fn tokenize(message: &str) -> Vec<Token<'_>> {
+  Tokenizer::new(message).run()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{Token, TokenKind, tokenize};
+
+
+  fn token(kind: TokenKind, text: &str, quoted: bool, spoiler: bool) -> Token<'_> {
+    Token {
+      kind,
+      text,
+      quoted,
+      spoiler
+    }
+  }
+
+
+  #[test]
+  fn quotes() {
+    assert_eq!(
+      tokenize("> quoted\nplain"),
+      vec![
+        token(TokenKind::Text, "quoted\n", true, false),
+        token(TokenKind::Text, "plain", false, false)
+      ]
+    );
+    assert_eq!(
+      tokenize("plain\n>>> quoted\nstill quoted"),
+      vec![
+        token(TokenKind::Text, "plain\n", false, false),
+        token(TokenKind::Text, "quoted\nstill quoted", true, false)
+      ]
+    );
+    assert_eq!(tokenize("a > b"), vec![token(TokenKind::Text, "a > b", false, false)]);
+  }
+
+  #[test]
+  fn spoilers() {
+    assert_eq!(
+      tokenize("a ||b|| c"),
+      vec![
+        token(TokenKind::Text, "a ", false, false),
+        token(TokenKind::Text, "b", false, true),
+        token(TokenKind::Text, " c", false, false)
+      ]
+    );
+    assert_eq!(tokenize("a || b"), vec![token(TokenKind::Text, "a || b", false, false)]);
+  }
+
+  #[test]
+  fn code() {
+    assert_eq!(
+      tokenize("a `b` c"),
+      vec![
+        token(TokenKind::Text, "a ", false, false),
+        token(TokenKind::InlineCode, "b", false, false),
+        token(TokenKind::Text, " c", false, false)
+      ]
+    );
+    assert_eq!(
+      tokenize("``a ` b``"),
+      vec![token(TokenKind::InlineCode, "a ` b", false, false)]
+    );
+    assert_eq!(
+      tokenize("```rs\nlet a = 1;\n```"),
+      vec![token(TokenKind::CodeBlock, "rs\nlet a = 1;\n", false, false)]
+    );
+    assert_eq!(
+      tokenize("> `a` ||`b`||"),
+      vec![
+        token(TokenKind::InlineCode, "a", true, false),
+        token(TokenKind::Text, " ", true, false),
+        token(TokenKind::InlineCode, "b", true, true)
+      ]
+    );
+  }
+
+  #[test]
+  fn escapes() {
+    assert_eq!(tokenize("\\`a\\`"), vec![token(TokenKind::Text, "\\`a\\`", false, false)]);
+    assert_eq!(tokenize("\\||a||"), vec![token(TokenKind::Text, "\\||a||", false, false)]);
+    assert_eq!(tokenize("a\\"), vec![token(TokenKind::Text, "a\\", false, false)]);
+  }
+
+  #[test]
+  fn unterminated_code() {
+    assert_eq!(tokenize("a `b"), vec![token(TokenKind::Text, "a `b", false, false)]);
+    assert_eq!(tokenize("```a"), vec![token(TokenKind::Text, "```a", false, false)]);
+    assert_eq!(tokenize("``"), vec![token(TokenKind::Text, "``", false, false)]);
+  }
+}
@@ -0,0 +1,925 @@
+use std::{
+  collections::{HashMap, HashSet, BTreeMap},
+  convert::Infallible,
+  io::Write,
+  net::SocketAddr,
+  sync::Arc
+};
+
+use flate2::{write::GzEncoder, Compression};
+use hyper::{
+  Server,
+  server::conn::AddrIncoming,
+  Body,
+  Request,
+  Response,
+  Method,
+  StatusCode,
+  header,
+  http::uri::PathAndQuery,
+  body::{self, Bytes},
+  service::{make_service_fn, service_fn}
+};
+use base64::{engine::general_purpose, Engine};
+use rand::{distributions::Alphanumeric, Rng};
+use serde_json::{Value, json};
+use sha2::{Sha256, Digest};
+use tokio::sync::Mutex;
+use tracing::log::{info, error};
+use anyhow::{Result, anyhow};
+
+use super::{
+  oauth::OAuthSession,
+  model::TwitterApiError,
+  signer::parse_form
+};
+
+
+pub static MOCK_APP_BEARER_TOKEN: &str = "mock-app-bearer-token";
+
+
+#[derive(Debug)]
+struct RequestToken {
+  secret: Arc<str>,
+  verifier: Arc<str>,
+  callback: Option<Arc<str>>
+}
+
+#[derive(Debug)]
+struct AccessToken {
+  secret: Arc<str>,
+  user_id: Arc<str>
+}
+
+#[derive(Debug)]
+struct VerifiedRequest {
+  token: Option<Arc<str>>,
+  callback: Option<Arc<str>>
+}
+
+#[derive(Debug)]
+struct AuthorizationCode {
+  code_challenge: Arc<str>,
+  redirect_uri: Arc<str>,
+  scope: Arc<str>
+}
+
+#[derive(Debug)]
+struct MockState {
+  base_url: Arc<str>,
+  consumer_key: Arc<str>,
+  consumer_secret: Arc<str>,
+  request_tokens: HashMap<Arc<str>, RequestToken>,
+  access_tokens: HashMap<Arc<str>, AccessToken>,
+  authorization_codes: HashMap<Arc<str>, AuthorizationCode>,
+  bearer_tokens: HashMap<Arc<str>, Arc<str>>,
+  refresh_tokens: HashMap<Arc<str>, (Arc<str>, Arc<str>)>,
+  likes: HashSet<(Arc<str>, Arc<str>)>,
+  retweets: HashSet<(Arc<str>, Arc<str>)>,
+  bookmarks: HashSet<(Arc<str>, Arc<str>)>,
+  friendships: HashSet<(Arc<str>, Arc<str>)>,
+  tweets: HashMap<Arc<str>, Arc<str>>,
+  next_user_id: u64,
+  next_tweet_id: u64
+}
+
+impl MockState {
+  fn random_token(length: usize) -> Arc<str> {
+    rand::thread_rng()
+      .sample_iter(&Alphanumeric)
+      .take(length)
+      .map(char::from)
+      .collect::<String>()
+      .into()
+  }
+
+  fn is_missing(tweet_id: &str) -> bool {
+    tweet_id.parse::<u64>().map_or(true, |tweet_id: u64| tweet_id % 1000 == 404)
+  }
+
+  fn author_of(tweet_id: &str) -> Arc<str> {
+    (
+      tweet_id.parse::<u64>().unwrap_or(0) % 1_000_000_007 + 1
+    ).to_string().into()
+  }
+
+  fn verify(
+    self: &Self,
+    request: &Request<Body>,
+    body: &[u8]
+  ) -> Result<VerifiedRequest> {
+    let authorization: &str = request.headers()
+      .get(header::AUTHORIZATION)
+      .ok_or(anyhow!("Missing Authorization header."))?
+      .to_str()?
+      .strip_prefix("OAuth ")
+      .ok_or(anyhow!("Not an OAuth Authorization header."))?;
+
+    let mut owned_params: Vec<(String, String)> = Vec::new();
+    for pair in authorization.split(", ") {
+      let (k, v): (&str, &str) = pair
+        .split_once("=")
+        .ok_or(anyhow!("Malformed OAuth parameter: {:?}", pair))?;
+
+      owned_params.push(
+        (
+          urlencoding::decode(k)?.into_owned(),
+          urlencoding::decode(v.trim_matches('"'))?.into_owned()
+        )
+      );
+    }
+
+    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
+    let mut signature: Option<&str> = None;
+    for (k, v) in owned_params.iter() {
+      match k.as_str() {
+        "oauth_signature" => signature = Some(v),
+        "realm" => (),
+        _ => { params.insert(k, v.as_str().into()); }
+      }
+    }
+
+    let mut signed_params: Vec<(Arc<str>, Arc<str>)> = params.iter()
+      .map(|(k, v)| (Arc::from(*k), v.clone()))
+      .collect();
+    if request.headers().get(header::CONTENT_TYPE).and_then(
+      |content_type: &header::HeaderValue| content_type.to_str().ok()
+    ) == Some("application/x-www-form-urlencoded") {
+      signed_params.extend(parse_form(&String::from_utf8_lossy(body))?);
+    }
+
+    let query_params: Vec<(Arc<str>, Arc<str>)> = parse_form(request.uri().query().unwrap_or(""))?;
+    for (k, v) in query_params.iter() {
+      params.entry(k).or_insert(v.clone());
+    }
+
+    if params.get("oauth_consumer_key").map(|key: &Arc<str>| key.as_ref()) != Some(&self.consumer_key) {
+      return Err(anyhow!("Unknown consumer key."));
+    }
+
+    let token: Option<Arc<str>> = params.get("oauth_token").cloned();
+    let token_secret: Option<Arc<str>> = match &token {
+      Some(token) => match (
+        self.request_tokens.get(token),
+        self.access_tokens.get(token)
+      ) {
+        (Some(request_token), _) => Some(request_token.secret.clone()),
+        (_, Some(access_token)) => Some(access_token.secret.clone()),
+        (None, None) => return Err(
+          TwitterApiError::new(StatusCode::UNAUTHORIZED, Some(89), "Invalid or expired token.").into()
+        )
+      },
+      None => None
+    };
+
+    let expected_signature: Arc<str> = OAuthSession::new(
+      self.base_url.clone(),
+      self.consumer_key.clone(),
+      self.consumer_secret.clone(),
+      token.clone(),
+      token_secret
+    )?.signature(
+      request.method(),
+      &format!(
+        "{base_url}{path_and_query}",
+        base_url = self.base_url,
+        path_and_query = request.uri()
+          .path_and_query()
+          .map_or("/", |path_and_query: &PathAndQuery| path_and_query.as_str())
+      ),
+      &signed_params
+    )?;
+
+    if signature != Some(&expected_signature) {
+      return Err(anyhow!("Signature mismatch."));
+    }
+
+    Ok(
+      VerifiedRequest {
+        token,
+        callback: params.get("oauth_callback").cloned()
+      }
+    )
+  }
+
+  fn handle(
+    self: &mut Self,
+    request: &Request<Body>,
+    body: &[u8]
+  ) -> (StatusCode, Arc<str>) {
+    let path: &str = request.uri().path();
+
+    if request.method() == Method::GET && path == "/oauth/authorize" {
+      return self.authorize(request);
+    }
+
+    if request.method() == Method::GET && path == "/i/oauth2/authorize" {
+      return self.oauth2_authorize(request);
+    }
+
+    if request.method() == Method::POST && path == "/2/oauth2/token" {
+      return self.oauth2_token(body);
+    }
+
+    if request.method() == Method::POST && path == "/2/oauth2/revoke" {
+      return self.oauth2_revoke(body);
+    }
+
+    let bearer_token: Option<&str> = request.headers()
+      .get(header::AUTHORIZATION)
+      .and_then(|value: &header::HeaderValue| value.to_str().ok())
+      .and_then(|value: &str| value.strip_prefix("Bearer "));
+
+    if let Some(bearer_token) = bearer_token {
+      return match (self.bearer_tokens.get(bearer_token).cloned(), path.strip_prefix("/2/")) {
+        (Some(user_id), Some(path)) => self.handle_v2(request.method(), path, body, user_id),
+        (Some(user_id), None) if request.method() == Method::GET && path == "/1.1/statuses/show.json" => self.handle_v1(
+          request.method(),
+          path,
+          &MockState::parse_query(request.uri().query().unwrap_or("")),
+          user_id
+        ),
+        (Some(_), None) => error_response(StatusCode::FORBIDDEN, 220, "Your credentials do not allow access to this resource."),
+        (None, _) => v2_error_response(StatusCode::UNAUTHORIZED, "Unauthorized")
+      };
+    }
+
+    let VerifiedRequest { token, callback } = match self.verify(request, body) {
+      Ok(verified_request) => verified_request,
+      Err(why) => {
+        error!("Mock signature check failed: {}", why);
+
+        return match why.downcast_ref::<TwitterApiError>() {
+          Some(error) => error_response(error.status, error.code.unwrap_or(32), &error.message),
+          None => error_response(StatusCode::UNAUTHORIZED, 32, "Could not authenticate you.")
+        };
+      }
+    };
+
+    let mut query: HashMap<String, String> = request.uri()
+      .query()
+      .into_iter()
+      .flat_map(|query: &str| query.split("&"))
+      .filter_map(|pair: &str| pair.split_once("="))
+      .map(
+        |(k, v)| (
+          k.to_string(),
+          urlencoding::decode(v).map(|v| v.into_owned()).unwrap_or_default()
+        )
+      )
+      .collect();
+
+    if request.headers().get(header::CONTENT_TYPE).and_then(
+      |content_type: &header::HeaderValue| content_type.to_str().ok()
+    ) == Some("application/x-www-form-urlencoded") {
+      query.extend(MockState::parse_query(&String::from_utf8_lossy(body)));
+    }
+
+    if path == "/oauth/request_token" {
+      return self.request_token(callback);
+    }
+
+    if path == "/oauth/access_token" {
+      return self.access_token(token, &query);
+    }
+
+    if request.method() == Method::POST && path == "/1.1/oauth/invalidate_token" {
+      return self.invalidate_token(token);
+    }
+
+    let user_id: Arc<str> = match token.as_ref().and_then(
+      |token: &Arc<str>| self.access_tokens.get(token)
+    ) {
+      Some(access_token) => access_token.user_id.clone(),
+      None => return error_response(StatusCode::UNAUTHORIZED, 89, "Invalid or expired token.")
+    };
+
+    match path.strip_prefix("/2/") {
+      Some(path) => self.handle_v2(request.method(), path, body, user_id),
+      None => self.handle_v1(request.method(), path, &query, user_id)
+    }
+  }
+
+  fn handle_v1(
+    self: &mut Self,
+    method: &Method,
+    path: &str,
+    query: &HashMap<String, String>,
+    user_id: Arc<str>
+  ) -> (StatusCode, Arc<str>) {
+    if method != Method::POST && !(method == Method::GET && matches!(path, "/1.1/statuses/lookup.json" | "/1.1/statuses/show.json" | "/1.1/account/verify_credentials.json")) {
+      return error_response(StatusCode::NOT_FOUND, 34, "Sorry, that page does not exist.");
+    }
+
+    let query_id: Arc<str> = query.get("id")
+      .or(query.get("user_id"))
+      .map(|id: &String| id.as_str().into())
+      .unwrap_or("".into());
+
+    let path_id: Arc<str> = path
+      .rsplit("/")
+      .next()
+      .and_then(|segment: &str| segment.strip_suffix(".json"))
+      .unwrap_or("")
+      .into();
+
+    match path {
+      "/1.1/favorites/create.json" => {
+        self.likes.insert((user_id, query_id.clone()));
+        tweet_response(&query_id)
+      },
+      "/1.1/favorites/destroy.json" => {
+        self.likes.remove(&(user_id, query_id.clone()));
+        tweet_response(&query_id)
+      },
+      "/1.1/statuses/lookup.json" if MockState::is_missing(&query_id) => (StatusCode::OK, "[]".into()),
+      "/1.1/statuses/lookup.json" => (
+        StatusCode::OK,
+        format!(
+          "[{tweet}]",
+          tweet = tweet_response(&query_id).1
+        ).into()
+      ),
+      "/1.1/statuses/show.json" if MockState::is_missing(&query_id) => {
+        error_response(StatusCode::NOT_FOUND, 144, "No status found with that ID.")
+      },
+      "/1.1/statuses/show.json" => {
+        let author_id: Arc<str> = MockState::author_of(&query_id);
+
+        (
+          StatusCode::OK,
+          json!(
+            {
+              "id_str": query_id,
+              "full_text": format!("Mock tweet {query_id}"),
+              "user": {
+                "id_str": author_id,
+                "name": "Mock User",
+                "screen_name": format!("mock_user_{author_id}")
+              },
+              "extended_entities": {
+                "media": [{ "media_url_https": format!("{}/media/{query_id}.jpg", self.base_url) }]
+              },
+              "favorite_count": self.likes.iter().filter(|(_, tweet_id)| *tweet_id == query_id).count(),
+              "retweet_count": self.retweets.iter().filter(|(_, tweet_id)| *tweet_id == query_id).count()
+            }
+          ).to_string().into()
+        )
+      },
+      "/1.1/account/verify_credentials.json" => (
+        StatusCode::OK,
+        json!(
+          {
+            "id_str": user_id,
+            "screen_name": format!("mock_user_{user_id}"),
+            "profile_image_url_https": format!("{}/profile/{user_id}.jpg", self.base_url)
+          }
+        ).to_string().into()
+      ),
+      "/1.1/friendships/create.json" => {
+        self.friendships.insert((user_id, query_id.clone()));
+        user_response(&query_id)
+      },
+      "/1.1/friendships/destroy.json" => {
+        self.friendships.remove(&(user_id, query_id.clone()));
+        user_response(&query_id)
+      },
+      "/1.1/statuses/update.json" => {
+        let status: &str = query.get("status").map_or("", String::as_str);
+        let reply_to: Option<&String> = query.get("in_reply_to_status_id");
+
+        if status.is_empty() {
+          return error_response(StatusCode::FORBIDDEN, 170, "Missing required parameter: status.");
+        }
+
+        if reply_to.is_some_and(|tweet_id: &String| MockState::is_missing(tweet_id)) {
+          return error_response(StatusCode::FORBIDDEN, 385, "You attempted to reply to a Tweet that is deleted or not visible to you.");
+        }
+
+        let tweet_id: Arc<str> = self.post_tweet(user_id.clone());
+
+        (
+          StatusCode::OK,
+          json!(
+            {
+              "id_str": tweet_id,
+              "text": status,
+              "in_reply_to_status_id_str": reply_to,
+              "user": { "id_str": user_id }
+            }
+          ).to_string().into()
+        )
+      },
+      _ if path.starts_with("/1.1/statuses/retweet/") => {
+        self.retweets.insert((user_id, path_id.clone()));
+        tweet_response(&path_id)
+      },
+      _ if path.starts_with("/1.1/statuses/unretweet/") => {
+        self.retweets.remove(&(user_id, path_id.clone()));
+        tweet_response(&path_id)
+      },
+      _ => error_response(StatusCode::NOT_FOUND, 34, "Sorry, that page does not exist.")
+    }
+  }
+
+  fn handle_v2(
+    self: &mut Self,
+    method: &Method,
+    path: &str,
+    body: &[u8],
+    user_id: Arc<str>
+  ) -> (StatusCode, Arc<str>) {
+    let segments: Vec<&str> = path.split("/").collect();
+    let body: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let body_id = |key: &str| -> Arc<str> {
+      body.get(key).and_then(Value::as_str).unwrap_or("").into()
+    };
+
+    if let ["users", source_user_id, ..] = segments.as_slice() {
+      if *source_user_id != "me" && *source_user_id != user_id.as_ref() {
+        return v2_error_response(StatusCode::FORBIDDEN, "You are not permitted to perform this action.");
+      }
+    }
+
+    match (method, segments.as_slice()) {
+      (&Method::GET, ["users", "me"]) => (
+        StatusCode::OK,
+        format!(
+          r#"{{"data":{{"id":"{user_id}","name":"Mock User","username":"mock_user_{user_id}","profile_image_url":"{base_url}/profile/{user_id}.jpg"}}}}"#,
+          base_url = self.base_url
+        ).into()
+      ),
+      (&Method::GET, ["tweets", tweet_id]) if MockState::is_missing(tweet_id) => (
+        StatusCode::OK,
+        format!(
+          r#"{{"errors":[{{"value":"{tweet_id}","detail":"Could not find tweet with id: [{tweet_id}].","title":"Not Found Error","resource_type":"tweet","type":"https://api.twitter.com/2/problems/resource-not-found"}}]}}"#
+        ).into()
+      ),
+      (&Method::POST, ["users", _, "likes" | "retweets" | "bookmarks"]) if MockState::is_missing(&body_id("tweet_id")) => {
+        v2_error_response(StatusCode::NOT_FOUND, "Could not find tweet.")
+      },
+      (&Method::GET, ["tweets", tweet_id]) => {
+        let author_id: Arc<str> = MockState::author_of(tweet_id);
+
+        (
+          StatusCode::OK,
+          json!(
+            {
+              "data": {
+                "id": tweet_id,
+                "text": format!("Mock tweet {tweet_id}"),
+                "author_id": author_id,
+                "attachments": { "media_keys": [format!("3_{tweet_id}")] },
+                "public_metrics": {
+                  "like_count": self.likes.iter().filter(|(_, liked_id)| liked_id.as_ref() == *tweet_id).count(),
+                  "retweet_count": self.retweets.iter().filter(|(_, retweeted_id)| retweeted_id.as_ref() == *tweet_id).count()
+                }
+              },
+              "includes": {
+                "users": [{ "id": author_id, "name": "Mock User", "username": format!("mock_user_{author_id}") }],
+                "media": [{ "media_key": format!("3_{tweet_id}"), "type": "photo", "url": format!("{}/media/{tweet_id}.jpg", self.base_url) }]
+              }
+            }
+          ).to_string().into()
+        )
+      },
+      (&Method::POST, ["users", _, "likes"]) => {
+        self.likes.insert((user_id, body_id("tweet_id")));
+        (StatusCode::OK, r#"{"data":{"liked":true}}"#.into())
+      },
+      (&Method::DELETE, ["users", _, "likes", tweet_id]) => {
+        self.likes.remove(&(user_id, (*tweet_id).into()));
+        (StatusCode::OK, r#"{"data":{"liked":false}}"#.into())
+      },
+      (&Method::POST, ["users", _, "retweets"]) => {
+        self.retweets.insert((user_id, body_id("tweet_id")));
+        (StatusCode::OK, r#"{"data":{"retweeted":true}}"#.into())
+      },
+      (&Method::DELETE, ["users", _, "retweets", tweet_id]) => {
+        self.retweets.remove(&(user_id, (*tweet_id).into()));
+        (StatusCode::OK, r#"{"data":{"retweeted":false}}"#.into())
+      },
+      (&Method::POST, ["users", _, "bookmarks"]) => {
+        self.bookmarks.insert((user_id, body_id("tweet_id")));
+        (StatusCode::OK, r#"{"data":{"bookmarked":true}}"#.into())
+      },
+      (&Method::DELETE, ["users", _, "bookmarks", tweet_id]) => {
+        self.bookmarks.remove(&(user_id, (*tweet_id).into()));
+        (StatusCode::OK, r#"{"data":{"bookmarked":false}}"#.into())
+      },
+      (&Method::POST, ["tweets"]) => {
+        let text: &str = body.get("text").and_then(Value::as_str).unwrap_or("");
+        let target_id: Option<&str> = body
+          .pointer("/reply/in_reply_to_tweet_id")
+          .or(body.get("quote_tweet_id"))
+          .and_then(Value::as_str);
+
+        if text.is_empty() {
+          return v2_error_response(StatusCode::BAD_REQUEST, "The `text` field is required.");
+        }
+
+        if target_id.is_some_and(MockState::is_missing) {
+          return v2_error_response(StatusCode::NOT_FOUND, "Could not find tweet.");
+        }
+
+        let tweet_id: Arc<str> = self.post_tweet(user_id);
+
+        (
+          StatusCode::CREATED,
+          json!({ "data": { "id": tweet_id, "text": text } }).to_string().into()
+        )
+      },
+      (&Method::POST, ["users", _, "following"]) => {
+        self.friendships.insert((user_id, body_id("target_user_id")));
+        (StatusCode::OK, r#"{"data":{"following":true,"pending_follow":false}}"#.into())
+      },
+      (&Method::DELETE, ["users", _, "following", target_user_id]) => {
+        self.friendships.remove(&(user_id, (*target_user_id).into()));
+        (StatusCode::OK, r#"{"data":{"following":false}}"#.into())
+      },
+      _ => v2_error_response(StatusCode::NOT_FOUND, "Sorry, that page does not exist.")
+    }
+  }
+
+  fn request_token(self: &mut Self, callback: Option<Arc<str>>) -> (StatusCode, Arc<str>) {
+    let token: Arc<str> = MockState::random_token(27);
+    let secret: Arc<str> = MockState::random_token(32);
+
+    self.request_tokens.insert(
+      token.clone(),
+      RequestToken {
+        secret: secret.clone(),
+        verifier: rand::thread_rng().gen_range(1_000_000..10_000_000).to_string().into(),
+        callback: callback.filter(|callback: &Arc<str>| callback.as_ref() != "oob")
+      }
+    );
+
+    (
+      StatusCode::OK,
+      format!(
+        "oauth_token={token}&oauth_token_secret={secret}&oauth_callback_confirmed=true"
+      ).into()
+    )
+  }
+
+  fn authorize(self: &Self, request: &Request<Body>) -> (StatusCode, Arc<str>) {
+    let token: &str = request.uri()
+      .query()
+      .and_then(|query: &str| query.strip_prefix("oauth_token="))
+      .unwrap_or("");
+
+    match self.request_tokens.get(token) {
+      Some(RequestToken { verifier, callback: Some(callback), .. }) => (
+        StatusCode::OK,
+        format!(
+          "<html><body><a href=\"{callback}?oauth_token={token}&oauth_verifier={verifier}\"><code>{callback}?oauth_token={token}&oauth_verifier={verifier}</code></a></body></html>"
+        ).into()
+      ),
+      Some(request_token) => (
+        StatusCode::OK,
+        format!(
+          "<html><body><code>{verifier}</code></body></html>",
+          verifier = request_token.verifier
+        ).into()
+      ),
+      None => error_response(StatusCode::UNAUTHORIZED, 89, "Invalid or expired token.")
+    }
+  }
+
+  fn access_token(
+    self: &mut Self,
+    token: Option<Arc<str>>,
+    query: &HashMap<String, String>
+  ) -> (StatusCode, Arc<str>) {
+    let request_token: RequestToken = match token.and_then(
+      |token: Arc<str>| self.request_tokens.remove(&token)
+    ) {
+      Some(request_token) => request_token,
+      None => return error_response(StatusCode::UNAUTHORIZED, 89, "Invalid or expired token.")
+    };
+
+    if query.get("oauth_verifier").map(|verifier: &String| verifier.as_str()) != Some(&request_token.verifier) {
+      return error_response(StatusCode::UNAUTHORIZED, 89, "Invalid or expired token.");
+    }
+
+    self.next_user_id += 1;
+
+    let user_id: Arc<str> = self.next_user_id.to_string().into();
+    let token: Arc<str> = format!("{user_id}-{token}", token = MockState::random_token(40)).into();
+    let secret: Arc<str> = MockState::random_token(45);
+
+    self.access_tokens.insert(
+      token.clone(),
+      AccessToken {
+        secret: secret.clone(),
+        user_id: user_id.clone()
+      }
+    );
+
+    (
+      StatusCode::OK,
+      format!(
+        "oauth_token={token}&oauth_token_secret={secret}&user_id={user_id}&screen_name=mock_user_{user_id}"
+      ).into()
+    )
+  }
+
+  fn invalidate_token(self: &mut Self, token: Option<Arc<str>>) -> (StatusCode, Arc<str>) {
+    match token.and_then(|token: Arc<str>| self.access_tokens.remove_entry(&token)) {
+      Some((token, _)) => (
+        StatusCode::OK,
+        json!({ "access_token": token }).to_string().into()
+      ),
+      None => error_response(StatusCode::UNAUTHORIZED, 89, "Invalid or expired token.")
+    }
+  }
+
+  fn post_tweet(self: &mut Self, user_id: Arc<str>) -> Arc<str> {
+    self.next_tweet_id += 1;
+
+    let tweet_id: Arc<str> = self.next_tweet_id.to_string().into();
+
+    self.tweets.insert(tweet_id.clone(), user_id);
+
+    tweet_id
+  }
+
+  fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+      .split("&")
+      .filter_map(|pair: &str| pair.split_once("="))
+      .map(
+        |(k, v)| (
+          k.to_string(),
+          urlencoding::decode(&v.replace("+", " ")).map(|v| v.into_owned()).unwrap_or_default()
+        )
+      )
+      .collect()
+  }
+
+  fn oauth2_authorize(self: &mut Self, request: &Request<Body>) -> (StatusCode, Arc<str>) {
+    let query: HashMap<String, String> = MockState::parse_query(
+      request.uri().query().unwrap_or("")
+    );
+
+    let (redirect_uri, state, code_challenge) = match (
+      query.get("redirect_uri"),
+      query.get("state"),
+      query.get("code_challenge")
+    ) {
+      (Some(redirect_uri), Some(state), Some(code_challenge))
+        if query.get("code_challenge_method").map(String::as_str) == Some("S256") => (
+        redirect_uri,
+        state,
+        code_challenge
+      ),
+      _ => return v2_error_response(StatusCode::BAD_REQUEST, "Invalid authorization request.")
+    };
+
+    let code: Arc<str> = MockState::random_token(40);
+
+    self.authorization_codes.insert(
+      code.clone(),
+      AuthorizationCode {
+        code_challenge: code_challenge.as_str().into(),
+        redirect_uri: redirect_uri.as_str().into(),
+        scope: query.get("scope").map(String::as_str).unwrap_or("").into()
+      }
+    );
+
+    (
+      StatusCode::OK,
+      format!(
+        "<html><body><a href=\"{redirect_uri}?state={state}&code={code}\"><code>{redirect_uri}?state={state}&code={code}</code></a></body></html>"
+      ).into()
+    )
+  }
+
+  fn issue_bearer_token(self: &mut Self, user_id: Arc<str>, scope: Arc<str>) -> (StatusCode, Arc<str>) {
+    let access_token: Arc<str> = MockState::random_token(48);
+    let refresh_token: Arc<str> = MockState::random_token(48);
+
+    self.bearer_tokens.insert(access_token.clone(), user_id.clone());
+    self.refresh_tokens.insert(refresh_token.clone(), (user_id, scope.clone()));
+
+    (
+      StatusCode::OK,
+      json!(
+        {
+          "token_type": "bearer",
+          "expires_in": 7200,
+          "access_token": access_token,
+          "refresh_token": refresh_token,
+          "scope": scope
+        }
+      ).to_string().into()
+    )
+  }
+
+  fn oauth2_token(self: &mut Self, body: &[u8]) -> (StatusCode, Arc<str>) {
+    let form: HashMap<String, String> = MockState::parse_query(
+      &String::from_utf8_lossy(body)
+    );
+    let field = |key: &str| -> &str {
+      form.get(key).map(String::as_str).unwrap_or("")
+    };
+
+    match field("grant_type") {
+      "authorization_code" => {
+        let authorization_code: AuthorizationCode = match self.authorization_codes.remove(field("code")) {
+          Some(authorization_code) => authorization_code,
+          None => return oauth2_error_response("invalid_request", "Value passed for the authorization code was invalid.")
+        };
+
+        let code_challenge: String = general_purpose::URL_SAFE_NO_PAD.encode(
+          Sha256::digest(field("code_verifier").as_bytes())
+        );
+
+        if code_challenge != authorization_code.code_challenge.as_ref()
+          || field("redirect_uri") != authorization_code.redirect_uri.as_ref() {
+          return oauth2_error_response("invalid_request", "Value passed for the authorization code was invalid.");
+        }
+
+        self.next_user_id += 1;
+
+        let user_id: Arc<str> = self.next_user_id.to_string().into();
+
+        self.issue_bearer_token(user_id, authorization_code.scope)
+      },
+      "refresh_token" => match self.refresh_tokens.remove(field("refresh_token")) {
+        Some((user_id, scope)) => self.issue_bearer_token(user_id, scope),
+        None => oauth2_error_response("invalid_request", "Value passed for the token was invalid.")
+      },
+      _ => oauth2_error_response("unsupported_grant_type", "Unsupported grant type.")
+    }
+  }
+
+  fn oauth2_revoke(self: &mut Self, body: &[u8]) -> (StatusCode, Arc<str>) {
+    let form: HashMap<String, String> = MockState::parse_query(
+      &String::from_utf8_lossy(body)
+    );
+    let token: &str = form.get("token").map(String::as_str).unwrap_or("");
+
+    let revoked: bool = self.bearer_tokens.remove(token).is_some()
+      || self.refresh_tokens.remove(token).is_some();
+
+    (
+      StatusCode::OK,
+      json!({ "revoked": revoked }).to_string().into()
+    )
+  }
+}
+
+
+fn error_response(status: StatusCode, code: u32, message: &str) -> (StatusCode, Arc<str>) {
+  (
+    status,
+    format!(
+      r#"{{"errors":[{{"code":{code},"message":"{message}"}}]}}"#
+    ).into()
+  )
+}
+
+fn v2_error_response(status: StatusCode, detail: &str) -> (StatusCode, Arc<str>) {
+  (
+    status,
+    format!(
+      r#"{{"title":"{title}","detail":"{detail}","status":{status}}}"#,
+      title = status.canonical_reason().unwrap_or(""),
+      status = status.as_u16()
+    ).into()
+  )
+}
+
+fn oauth2_error_response(error: &str, description: &str) -> (StatusCode, Arc<str>) {
+  (
+    StatusCode::BAD_REQUEST,
+    json!({ "error": error, "error_description": description }).to_string().into()
+  )
+}
+
+fn tweet_response(tweet_id: &str) -> (StatusCode, Arc<str>) {
+  if MockState::is_missing(tweet_id) {
+    return error_response(StatusCode::NOT_FOUND, 144, "No status found with that ID.");
+  }
+
+  (
+    StatusCode::OK,
+    format!(
+      r#"{{"id":{tweet_id},"id_str":"{tweet_id}","user":{{"id":{author_id},"id_str":"{author_id}"}}}}"#,
+      author_id = MockState::author_of(tweet_id)
+    ).into()
+  )
+}
+
+fn user_response(user_id: &str) -> (StatusCode, Arc<str>) {
+  (
+    StatusCode::OK,
+    format!(
+      r#"{{"id":{user_id},"id_str":"{user_id}","screen_name":"mock_user_{user_id}"}}"#
+    ).into()
+  )
+}
+
+
+#[derive(Debug)]
+pub struct MockTwitterServer {
+  base_url: Arc<str>
+}
+
+impl MockTwitterServer {
+  pub async fn start(
+    address: SocketAddr,
+    consumer_key: Arc<str>,
+    consumer_secret: Arc<str>
+  ) -> Result<MockTwitterServer> {
+    let incoming: AddrIncoming = AddrIncoming::bind(&address)?;
+    let base_url: Arc<str> = format!(
+      "http://{address}",
+      address = incoming.local_addr()
+    ).into();
+
+    let state: Arc<Mutex<MockState>> = Arc::new(
+      Mutex::new(
+        MockState {
+          base_url: base_url.clone(),
+          consumer_key,
+          consumer_secret,
+          request_tokens: HashMap::new(),
+          access_tokens: HashMap::new(),
+          authorization_codes: HashMap::new(),
+          bearer_tokens: HashMap::from([(MOCK_APP_BEARER_TOKEN.into(), "0".into())]),
+          refresh_tokens: HashMap::new(),
+          likes: HashSet::new(),
+          retweets: HashSet::new(),
+          bookmarks: HashSet::new(),
+          friendships: HashSet::new(),
+          tweets: HashMap::new(),
+          next_user_id: 0,
+          next_tweet_id: 1_000_000_000_000_000_000
+        }
+      )
+    );
+
+    let server = Server::builder(incoming).serve(
+      make_service_fn(
+        move |_| {
+          let state: Arc<Mutex<MockState>> = state.clone();
+
+          async move {
+            Ok::<_, Infallible>(
+              service_fn(
+                move |mut request: Request<Body>| {
+                  let state: Arc<Mutex<MockState>> = state.clone();
+
+                  async move {
+                    info!("Mock request `{} {}`", request.method(), request.uri());
+
+                    let body: Bytes = body::to_bytes(request.body_mut()).await.unwrap_or_default();
+                    let (status, body): (StatusCode, Arc<str>) = state.lock().await.handle(&request, &body);
+
+                    Ok::<_, Infallible>(encode_response(status, &body))
+                  }
+                }
+              )
+            )
+          }
+        }
+      )
+    );
+
+    tokio::spawn(
+      async move {
+        if let Err(why) = server.await {
+          error!("Mock server error: {:?}", why);
+        }
+      }
+    );
+
+    Ok(MockTwitterServer { base_url })
+  }
+
+  pub fn base_url(self: &Self) -> Arc<str> {
+    self.base_url.clone()
+  }
+}
+
+
+fn encode_response(status: StatusCode, body: &str) -> Response<Body> {
+  let mut encoder: GzEncoder<Vec<u8>> = GzEncoder::new(Vec::new(), Compression::default());
+
+  let encoded: Vec<u8> = match encoder.write_all(body.as_bytes()).and_then(|_| encoder.finish()) {
+    Ok(encoded) => encoded,
+    Err(why) => {
+      error!("Encode mock response failed: {:?}", why);
+      Vec::new()
+    }
+  };
+
+  Response::builder()
+    .status(status)
+    .header(header::CONTENT_ENCODING, "gzip")
+    .header(header::CONTENT_LENGTH, encoded.len())
+    .body(Body::from(encoded))
+    .unwrap_or_default()
+}
@@ -1,3 +1,22 @@
 pub mod oauth;
 pub mod utils;
 pub mod cache;
+pub mod ttl;
+pub mod store;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod api;
+pub mod oauth2;
+pub mod callback;
+pub mod signer;
+pub mod model;
+pub mod error;
+pub mod ratelimit;
+pub mod http;
+pub mod settings;
+pub mod action;
+pub mod link;
+pub mod markdown;
+pub mod lock;
+pub mod embed;
+pub mod expiry;
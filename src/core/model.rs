@@ -0,0 +1,422 @@
+use std::{
+  sync::Arc,
+  fmt::{Display, Formatter}
+};
+
+use hyper::StatusCode;
+use serde::Deserialize;
+
+
+pub fn get_tweet_url(tweet_id: &str) -> Arc<str> {
+  format!("https://twitter.com/i/web/status/{tweet_id}").into()
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Tweet {
+  pub id: Arc<str>,
+  pub author_id: Arc<str>,
+  pub text: Arc<str>
+}
+
+impl Tweet {
+  pub fn url(self: &Self) -> Arc<str> {
+    get_tweet_url(&self.id)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TweetPreview {
+  pub id: Arc<str>,
+  pub text: Arc<str>,
+  pub author: TweetAuthor,
+  pub media: Vec<Arc<str>>,
+  pub like_count: u64,
+  pub retweet_count: u64
+}
+
+impl TweetPreview {
+  pub fn url(self: &Self) -> Arc<str> {
+    get_tweet_url(&self.id)
+  }
+}
+
+impl From<&TweetPreview> for Tweet {
+  fn from(preview: &TweetPreview) -> Tweet {
+    Tweet {
+      id: preview.id.clone(),
+      author_id: preview.author.id.clone(),
+      text: preview.text.clone()
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TweetAuthor {
+  pub id: Arc<str>,
+  pub name: Arc<str>,
+  pub username: Arc<str>,
+  pub profile_image_url: Option<Arc<str>>
+}
+
+impl TweetAuthor {
+  pub fn url(self: &Self) -> Arc<str> {
+    format!("https://twitter.com/{}", self.username).into()
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct TwitterUser {
+  pub id: Arc<str>,
+  pub username: Arc<str>,
+  pub profile_image_url: Option<Arc<str>>
+}
+
+impl TwitterUser {
+  pub fn url(self: &Self) -> Arc<str> {
+    format!("https://twitter.com/{}", self.username).into()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Like {
+  pub liked: bool
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Retweet {
+  pub retweeted: bool
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Bookmark {
+  pub bookmarked: bool
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Friendship {
+  pub following: bool,
+  #[serde(default)]
+  pub pending_follow: bool
+}
+
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1UserRef {
+  pub id_str: Arc<str>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1Tweet {
+  pub id_str: Arc<str>,
+  #[serde(default, alias = "full_text")]
+  pub text: Arc<str>,
+  pub user: V1UserRef
+}
+
+impl From<V1Tweet> for Tweet {
+  fn from(tweet: V1Tweet) -> Tweet {
+    Tweet {
+      id: tweet.id_str,
+      author_id: tweet.user.id_str,
+      text: tweet.text
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1PreviewUser {
+  pub id_str: Arc<str>,
+  pub name: Arc<str>,
+  pub screen_name: Arc<str>,
+  pub profile_image_url_https: Option<Arc<str>>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1Media {
+  pub media_url_https: Arc<str>
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct V1Entities {
+  #[serde(default)]
+  pub media: Vec<V1Media>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1TweetPreview {
+  pub id_str: Arc<str>,
+  #[serde(default, alias = "full_text")]
+  pub text: Arc<str>,
+  pub user: V1PreviewUser,
+  #[serde(default)]
+  pub extended_entities: V1Entities,
+  #[serde(default)]
+  pub favorite_count: u64,
+  #[serde(default)]
+  pub retweet_count: u64
+}
+
+impl From<V1TweetPreview> for TweetPreview {
+  fn from(tweet: V1TweetPreview) -> TweetPreview {
+    TweetPreview {
+      id: tweet.id_str,
+      text: tweet.text,
+      author: TweetAuthor {
+        id: tweet.user.id_str,
+        name: tweet.user.name,
+        username: tweet.user.screen_name,
+        profile_image_url: tweet.user.profile_image_url_https
+      },
+      media: tweet.extended_entities.media
+        .into_iter()
+        .map(|media: V1Media| media.media_url_https)
+        .collect(),
+      like_count: tweet.favorite_count,
+      retweet_count: tweet.retweet_count
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1User {
+  pub id_str: Arc<str>,
+  pub screen_name: Arc<str>,
+  pub profile_image_url_https: Option<Arc<str>>,
+  #[serde(default)]
+  pub follow_request_sent: bool
+}
+
+impl From<V1User> for TwitterUser {
+  fn from(user: V1User) -> TwitterUser {
+    TwitterUser {
+      id: user.id_str,
+      username: user.screen_name,
+      profile_image_url: user.profile_image_url_https
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1Error {
+  pub code: u32,
+  pub message: Arc<str>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V1ErrorResponse {
+  pub errors: Vec<V1Error>
+}
+
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2Response<T> {
+  pub data: Option<T>,
+  #[serde(default)]
+  pub includes: V2Includes,
+  #[serde(default)]
+  pub errors: Vec<V2Problem>
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct V2Includes {
+  #[serde(default)]
+  pub users: Vec<V2PreviewUser>,
+  #[serde(default)]
+  pub media: Vec<V2Media>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2PreviewUser {
+  pub id: Arc<str>,
+  pub name: Arc<str>,
+  pub username: Arc<str>,
+  pub profile_image_url: Option<Arc<str>>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2Media {
+  pub media_key: Arc<str>,
+  pub url: Option<Arc<str>>,
+  pub preview_image_url: Option<Arc<str>>
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct V2PublicMetrics {
+  #[serde(default)]
+  pub like_count: u64,
+  #[serde(default)]
+  pub retweet_count: u64
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct V2Attachments {
+  #[serde(default)]
+  pub media_keys: Vec<Arc<str>>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2TweetPreview {
+  pub id: Arc<str>,
+  #[serde(default)]
+  pub text: Arc<str>,
+  pub author_id: Option<Arc<str>>,
+  #[serde(default)]
+  pub public_metrics: V2PublicMetrics,
+  #[serde(default)]
+  pub attachments: V2Attachments
+}
+
+impl V2TweetPreview {
+  pub(crate) fn into_preview(self: Self, includes: V2Includes) -> Option<TweetPreview> {
+    let author: V2PreviewUser = includes.users
+      .into_iter()
+      .find(|user: &V2PreviewUser| Some(&user.id) == self.author_id.as_ref())?;
+
+    Some(
+      TweetPreview {
+        id: self.id,
+        text: self.text,
+        author: TweetAuthor {
+          id: author.id,
+          name: author.name,
+          username: author.username,
+          profile_image_url: author.profile_image_url
+        },
+        media: self.attachments.media_keys
+          .iter()
+          .filter_map(
+            |media_key: &Arc<str>| includes.media
+              .iter()
+              .find(|media: &&V2Media| &media.media_key == media_key)
+          )
+          .filter_map(|media: &V2Media| media.url.clone().or(media.preview_image_url.clone()))
+          .collect(),
+        like_count: self.public_metrics.like_count,
+        retweet_count: self.public_metrics.retweet_count
+      }
+    )
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2Tweet {
+  pub id: Arc<str>,
+  #[serde(default)]
+  pub text: Arc<str>,
+  pub author_id: Option<Arc<str>>
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2User {
+  pub id: Arc<str>,
+  pub username: Arc<str>,
+  pub profile_image_url: Option<Arc<str>>
+}
+
+impl From<V2User> for TwitterUser {
+  fn from(user: V2User) -> TwitterUser {
+    TwitterUser {
+      id: user.id,
+      username: user.username,
+      profile_image_url: user.profile_image_url
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct V2Problem {
+  pub title: Option<Arc<str>>,
+  pub detail: Option<Arc<str>>,
+  #[serde(rename = "type")]
+  pub problem_type: Option<Arc<str>>,
+  pub status: Option<u16>,
+  pub error: Option<Arc<str>>,
+  pub error_description: Option<Arc<str>>
+}
+
+impl V2Problem {
+  fn status(self: &Self) -> StatusCode {
+    if let Some(status) = self.status.and_then(|status: u16| StatusCode::from_u16(status).ok()) {
+      return status;
+    }
+
+    match self.problem_type.as_deref() {
+      Some("https://api.twitter.com/2/problems/resource-not-found") => StatusCode::NOT_FOUND,
+      Some("https://api.twitter.com/2/problems/not-authorized-for-resource") => StatusCode::FORBIDDEN,
+      Some("https://api.twitter.com/2/problems/usage-capped") => StatusCode::TOO_MANY_REQUESTS,
+      _ => StatusCode::BAD_REQUEST
+    }
+  }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct TwitterApiError {
+  pub status: StatusCode,
+  pub code: Option<u32>,
+  pub problem_type: Option<Arc<str>>,
+  pub oauth_error: Option<Arc<str>>,
+  pub message: Arc<str>,
+  pub rate_limit_reset: Option<u64>
+}
+
+impl Display for TwitterApiError {
+  fn fmt(self: &Self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self.code {
+      Some(code) => write!(f, "Twitter API error {} ({}): {}", self.status, code, self.message),
+      None => write!(f, "Twitter API error {}: {}", self.status, self.message)
+    }
+  }
+}
+
+impl std::error::Error for TwitterApiError {}
+
+impl TwitterApiError {
+  pub fn new(status: StatusCode, code: Option<u32>, message: &str) -> TwitterApiError {
+    TwitterApiError {
+      status,
+      code,
+      problem_type: None,
+      oauth_error: None,
+      message: message.into(),
+      rate_limit_reset: None
+    }
+  }
+
+  pub(crate) fn from_problem(problem: &V2Problem) -> TwitterApiError {
+    TwitterApiError {
+      status: problem.status(),
+      code: None,
+      problem_type: problem.problem_type.clone(),
+      oauth_error: problem.error.clone(),
+      message: problem.detail
+        .clone()
+        .or(problem.title.clone())
+        .or(problem.error_description.clone())
+        .unwrap_or("Unknown error.".into()),
+      rate_limit_reset: None
+    }
+  }
+
+  pub(crate) fn from_response(status: StatusCode, body: &str) -> TwitterApiError {
+    if let Ok(response) = serde_json::from_str::<V1ErrorResponse>(body) {
+      if let Some(error) = response.errors.first() {
+        return TwitterApiError::new(status, Some(error.code), &error.message);
+      }
+    }
+
+    if let Ok(problem) = serde_json::from_str::<V2Problem>(body) {
+      return TwitterApiError {
+        status,
+        ..TwitterApiError::from_problem(&problem)
+      };
+    }
+
+    TwitterApiError::new(
+      status,
+      None,
+      status.canonical_reason().unwrap_or("Unknown error.")
+    )
+  }
+}
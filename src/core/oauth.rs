@@ -1,18 +1,16 @@
 use std::{
   collections::{HashMap, BTreeMap},
   time::{SystemTime, UNIX_EPOCH},
-  io::Read,
   sync::{OnceLock, Arc},
   env
 };
 
 use base64::{engine::general_purpose, Engine};
-use flate2::read::GzDecoder;
 use hmac::{Hmac, Mac};
 use itertools::Itertools;
 use rust_i18n::t;
 use serenity::{
-  model::{user::User, prelude::Message},
+  model::{user::User, prelude::{Message, PrivateChannel, GuildId}},
   prelude::Context,
   utils::Color,
   builder::{
@@ -21,41 +19,106 @@ use serenity::{
     CreateEmbedFooter
   }
 };
-use tracing::debug;
-use regex::Regex;
+use tokio::time::{sleep, timeout};
+use tracing::log::{debug, warn};
 use sha1::Sha1;
 use hyper::{
   Client,
   Body,
   client::HttpConnector,
   Request,
-  body,
   Response,
+  Method,
   http::request::Builder,
-  header
+  header,
+  StatusCode
 };
 use hyper_rustls::{
   HttpsConnector as rustls_HttpsConnector,
-  HttpsConnectorBuilder
+  HttpsConnectorBuilder,
+  builderstates::WantsProtocols1
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
 use anyhow::{Result, anyhow, bail};
 
-use crate::core::utils::EMBED_ERROR_COLOR;
-
-use super::cache::AccessTokenCache;
+use crate::core::utils::{EMBED_ERROR_COLOR, clean_up_dm};
+
+use super::{
+  cache::{AccessTokenCache, CacheData, TweetCache},
+  action::TweetAction,
+  error::TwitterError,
+  expiry::{expire_credential, is_revoked, is_grant_revoked},
+  http::{
+    ACCEPT_ENCODING,
+    MAX_RETRIES,
+    REQUEST_TIMEOUT,
+    is_idempotent,
+    is_retryable_status,
+    read_body,
+    retry_delay
+  },
+  api::{TwitterApi, TwitterApiV2, get_api},
+  callback::get_callback_url,
+  model::{Tweet, TweetPreview, TwitterUser, Like, Retweet, Bookmark, Friendship, TwitterApiError},
+  ratelimit::{RateLimiter, RateLimitKey},
+  lock::{KeyedLock, KeyedGuard},
+  oauth2::{OAuth2Client, REFRESH_MARGIN},
+  signer::{percent_encode, generate_nonce, signature_base_string},
+  store::{Credential, StoredToken, StoredAccounts}
+};
 
 
 type HmacSha1 = Hmac<Sha1>;
 type HttpsConnector = rustls_HttpsConnector<HttpConnector>;
 
 
-static LOOKUP_USER_ID_REGEX: OnceLock<Regex> = OnceLock::new();
 static HTTP_CLIENT: OnceLock<Client<HttpsConnector, Body>> = OnceLock::new();
-static RNG: OnceLock<StdRng> = OnceLock::new();
+static REFRESH_LOCKS: OnceLock<KeyedLock<u64>> = OnceLock::new();
+
+pub static DEFAULT_API_BASE_URL: &str = "https://api.twitter.com";
 
+pub static TWITTER_API_BASE_URL: OnceLock<Arc<str>> = OnceLock::new();
 pub static TWITTER_CONSUMER_KEY: OnceLock<Arc<str>> = OnceLock::new();
 pub static TWITTER_CONSUMER_SECRET: OnceLock<Arc<str>> = OnceLock::new();
+pub static TWITTER_BEARER_TOKEN: OnceLock<Option<Arc<str>>> = OnceLock::new();
+
+
+pub fn get_consumer_key() -> Arc<str> {
+  TWITTER_CONSUMER_KEY.get_or_init(
+    || {
+      env::var("TWITTER_CONSUMER_KEY")
+        .expect("TWITTER_CONSUMER_KEY is not set.")
+        .into()
+    }
+  ).clone()
+}
+
+pub fn get_consumer_secret() -> Arc<str> {
+  TWITTER_CONSUMER_SECRET.get_or_init(
+    || {
+      env::var("TWITTER_CONSUMER_SECRET")
+        .expect("TWITTER_CONSUMER_SECRET is not set.")
+        .into()
+    }
+  ).clone()
+}
+
+pub fn get_bearer_token() -> Option<Arc<str>> {
+  TWITTER_BEARER_TOKEN.get_or_init(
+    || env::var("TWITTER_BEARER_TOKEN").ok().map(Arc::from)
+  ).clone()
+}
+
+
+pub fn get_api_base_url() -> Arc<str> {
+  TWITTER_API_BASE_URL.get_or_init(
+    || {
+      env::var("TWITTER_API_BASE_URL")
+        .unwrap_or(DEFAULT_API_BASE_URL.to_string())
+        .trim_end_matches('/')
+        .into()
+    }
+  ).clone()
+}
 
 
 #[derive(Debug)]
@@ -64,7 +127,9 @@ pub struct TwitterClient {
   request_token_secret: Option<Arc<str>>,
   access_token: Option<Arc<str>>,
   access_token_secret: Option<Arc<str>>,
-  oauth: OAuthSession
+  api: Arc<dyn TwitterApi>,
+  oauth: OAuthSession,
+  user_id: Option<u64>
 }
 
 unsafe impl Send for TwitterClient {}
@@ -73,6 +138,66 @@ impl TwitterClient {
   pub fn new(
     access_token: Option<Arc<str>>,
     access_token_secret: Option<Arc<str>>
+  ) -> Result<TwitterClient> {
+    TwitterClient::with_base_url(
+      get_api_base_url(),
+      access_token,
+      access_token_secret
+    )
+  }
+
+  pub fn from_credential(credential: &Credential) -> Result<TwitterClient> {
+    match credential {
+      Credential::OAuth1 { access_token, access_token_secret } => TwitterClient::new(
+        Some(access_token.clone()),
+        Some(access_token_secret.clone())
+      ),
+      Credential::OAuth2 { access_token, scope, .. } => {
+        let mut oauth: OAuthSession = OAuthSession::bearer(
+          get_api_base_url(),
+          access_token.clone()
+        )?;
+        oauth.granted_scope = scope.clone();
+
+        Ok(
+          TwitterClient {
+            request_token: None,
+            request_token_secret: None,
+            access_token: Some(access_token.clone()),
+            access_token_secret: None,
+            api: Arc::new(TwitterApiV2),
+            oauth,
+            user_id: None
+          }
+        )
+      }
+    }
+  }
+
+  pub fn get_app_client() -> Result<TwitterClient> {
+    let bearer_token: Arc<str> = get_bearer_token()
+      .ok_or(anyhow!("TWITTER_BEARER_TOKEN is not set."))?;
+
+    Ok(
+      TwitterClient {
+        request_token: None,
+        request_token_secret: None,
+        access_token: None,
+        access_token_secret: None,
+        api: get_api(),
+        oauth: OAuthSession::bearer(
+          get_api_base_url(),
+          bearer_token
+        )?,
+        user_id: None
+      }
+    )
+  }
+
+  pub fn with_base_url(
+    base_url: Arc<str>,
+    access_token: Option<Arc<str>>,
+    access_token_secret: Option<Arc<str>>
   ) -> Result<TwitterClient> {
     Ok(
       TwitterClient {
@@ -80,24 +205,15 @@ impl TwitterClient {
         request_token_secret: None,
         access_token: access_token.clone(),
         access_token_secret: access_token_secret.clone(),
+        api: get_api(),
         oauth: OAuthSession::new(
-          TWITTER_CONSUMER_KEY.get_or_init(
-            || {
-              env::var("TWITTER_CONSUMER_KEY")
-                .expect("TWITTER_CONSUMER_KEY is not set.")
-                .into()
-            }
-          ).clone(),
-          TWITTER_CONSUMER_SECRET.get_or_init(
-            || {
-              env::var("TWITTER_CONSUMER_SECRET")
-                .expect("TWITTER_CONSUMER_SECRET is not set.")
-                .into()
-            }
-          ).clone(),
+          base_url,
+          get_consumer_key(),
+          get_consumer_secret(),
           access_token,
           access_token_secret
-        )?
+        )?,
+        user_id: None
       }
     )
   }
@@ -136,62 +252,255 @@ impl TwitterClient {
     Ok(())
   }
 
-  pub async fn get_client(context: &Context, user: User) -> Result<TwitterClient> {
-    let cache: &AccessTokenCache = AccessTokenCache::get();
+  async fn migrate_legacy_token(
+    context: &Context,
+    user: &User
+  ) -> Result<Option<StoredToken>> {
+    let dm_channel: PrivateChannel = user.create_dm_channel(&context.http).await?;
 
-    if let Some(cache_data) = cache.request(
-      *user.id.as_u64()
-    ).await {
-      return Ok(
-        TwitterClient::new(
-          Some(cache_data.access_token),
-          Some(cache_data.access_token_secret)
-        )?
+    let pinned_message: Message = match dm_channel.pins(&context.http).await?.first() {
+      Some(pinned_message) => pinned_message.to_owned(),
+      None => return Ok(None)
+    };
+
+    if !pinned_message.content.contains("Twitter User Access Token") {
+      return Ok(None);
+    }
+
+    let token: Option<StoredToken> = pinned_message.content
+      .split("\n")
+      .skip(1)
+      .map(|s: &str| s.replace("`", "").replace("||", ""))
+      .collect_tuple::<(String, String)>()
+      .map(
+        |(access_token, access_token_secret)| StoredToken::unidentified(
+          Credential::OAuth1 {
+            access_token: access_token.into(),
+            access_token_secret: access_token_secret.into()
+          },
+          "".into()
+        )
       );
+
+    if let Some(token) = &token {
+      AccessTokenCache::get().add(*user.id.as_u64(), token.clone()).await?;
+
+      clean_up_dm(context, &dm_channel).await?;
     }
 
-    let pinned_message: Message = match user.create_dm_channel(
-      &context.http
-    ).await?.pins(&context.http).await?.first() {
-      Some(pinned_message) => pinned_message.to_owned(),
-      None => {
-        match TwitterClient::send_notify(context, &user).await {
-          Ok(()) => bail!("Pinned message not found."),
-          Err(_) => bail!("Send notify failed.")
+    Ok(token)
+  }
+
+  fn pending_refresh(credential: &Credential) -> Result<Option<Arc<str>>> {
+    match credential {
+      Credential::OAuth2 { refresh_token: Some(refresh_token), expires_at, .. } => {
+        let current_time: u64 = SystemTime::now()
+          .duration_since(UNIX_EPOCH)?
+          .as_secs();
+
+        match current_time + REFRESH_MARGIN < *expires_at {
+          true => Ok(None),
+          false => Ok(Some(refresh_token.clone()))
         }
+      },
+      _ => Ok(None)
+    }
+  }
+
+  async fn refresh_credential(
+    user_id: u64,
+    account_id: &str,
+    credential: Credential
+  ) -> Result<Credential> {
+    if TwitterClient::pending_refresh(&credential)?.is_none() {
+      return Ok(credential);
+    }
+
+    let _refreshing: KeyedGuard<'_, u64> = REFRESH_LOCKS
+      .get_or_init(KeyedLock::new)
+      .lock(user_id)
+      .await;
+
+    TwitterClient::refresh_stored_credential(user_id, account_id, credential).await
+  }
+
+  async fn refresh_stored_credential(
+    user_id: u64,
+    account_id: &str,
+    credential: Credential
+  ) -> Result<Credential> {
+    let credential: Credential = AccessTokenCache::get()
+      .load(user_id)
+      .await?
+      .and_then(
+        |accounts: StoredAccounts| accounts
+          .find(account_id)
+          .map(|token: &StoredToken| token.credential.clone())
+      )
+      .unwrap_or(credential);
+
+    let refresh_token: Arc<str> = match TwitterClient::pending_refresh(&credential)? {
+      Some(refresh_token) => refresh_token,
+      None => return Ok(credential)
+    };
+
+    debug!("Refreshing OAuth 2.0 token | User: {}", user_id);
+
+    let refreshed_credential: Credential = OAuth2Client::new()?
+      .refresh(&refresh_token)
+      .await?;
+
+    AccessTokenCache::get().update(
+      user_id,
+      |accounts: &mut StoredAccounts| {
+        accounts.update_credential(account_id, refreshed_credential.clone())
+      }
+    ).await?;
+
+    Ok(refreshed_credential)
+  }
+
+  pub async fn from_token(user_id: u64, token: &StoredToken) -> Result<TwitterClient> {
+    let credential: Credential = match TwitterClient::refresh_credential(
+      user_id,
+      &token.account_id,
+      token.credential.clone()
+    ).await {
+      Ok(credential) => credential,
+      Err(why) if is_grant_revoked(&why) => {
+        expire_credential(user_id, token.credential.access_token()).await;
+        return Err(why.context(TwitterError::Revoked));
+      },
+      Err(why) => return Err(why)
+    };
+
+    let mut twitter_client: TwitterClient = TwitterClient::from_credential(&credential)?;
+    twitter_client.user_id = Some(user_id);
+
+    Ok(twitter_client)
+  }
+
+  async fn identify_account(self: &mut Self, user_id: u64, placeholder: &str) {
+    let account: TwitterUser = match self.get_me().await {
+      Ok(account) => account,
+      Err(why) => {
+        warn!("Identify account failed: {:?} | User: {}", why, user_id);
+        return;
       }
     };
 
-    if !pinned_message.content.contains("Twitter User Access Token") {
-      match TwitterClient::send_notify(context, &user).await {
-        Ok(()) => bail!("Access Token not found."),
-        Err(_) => bail!("Send notify failed.")
+    debug!("Identified account `{}` | User: {} | ID: {}", account.username, user_id, account.id);
+
+    if let Err(why) = AccessTokenCache::get().update(
+      user_id,
+      |accounts: &mut StoredAccounts| accounts.identify(placeholder, account.id, account.username)
+    ).await {
+      warn!("Save account identity failed: {:?} | User: {}", why, user_id);
+    }
+  }
+
+  pub async fn get_client(
+    context: &Context,
+    user: User,
+    guild_id: Option<GuildId>
+  ) -> Result<TwitterClient> {
+    match TwitterClient::find_client(context, &user, guild_id).await {
+      Err(why) if TwitterError::classify(&why) == TwitterError::NotConnected => {
+        match TwitterClient::send_notify(context, &user).await {
+          Ok(()) => Err(why),
+          Err(_) => bail!("Send notify failed.")
+        }
+      },
+      result => result
+    }
+  }
+
+  pub async fn find_client(
+    context: &Context,
+    user: &User,
+    guild_id: Option<GuildId>
+  ) -> Result<TwitterClient> {
+    let user_id: u64 = *user.id.as_u64();
+
+    let token: Option<StoredToken> = AccessTokenCache::get()
+      .request(user_id)
+      .await?
+      .and_then(
+        |cache_data: CacheData| cache_data.accounts
+          .select(guild_id.map(|guild_id: GuildId| *guild_id.as_u64()))
+          .cloned()
+      );
+
+    if let Some(token) = token {
+      let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, &token).await?;
+
+      if !token.is_identified() {
+        twitter_client.identify_account(user_id, &token.account_id).await;
       }
+
+      return Ok(twitter_client);
     }
 
-    match pinned_message.content.split("\n").skip(1).map(
-      |s: &str| s.replace("`", "").replace("||", "")
-    ).collect_tuple::<(String, String)>() {
-      Some((access_token, access_token_secret)) => {
-        cache.add(
-          *user.id.as_u64(),
-          access_token.as_str(),
-          access_token_secret.as_str()
-        ).await;
-  
-        TwitterClient::new(
-          Some(access_token.into()),
-          Some(access_token_secret.into())
-        )
+    match TwitterClient::migrate_legacy_token(context, user).await? {
+      Some(token) => {
+        let mut twitter_client: TwitterClient = TwitterClient::from_credential(&token.credential)?;
+        twitter_client.user_id = Some(user_id);
+        twitter_client.identify_account(user_id, &token.account_id).await;
+
+        Ok(twitter_client)
       },
-      None => bail!("Token collect failed.")
+      None => Err(TwitterError::NotConnected.into())
+    }
+  }
+
+  pub async fn revoke_credential(credential: &Credential) -> Result<()> {
+    match credential {
+      Credential::OAuth1 { .. } => TwitterClient::from_credential(credential)?
+        .invalidate_token()
+        .await,
+      Credential::OAuth2 { access_token, refresh_token, .. } => {
+        let mut oauth2_client: OAuth2Client = OAuth2Client::new()?;
+
+        let refresh_result: Result<()> = match refresh_token {
+          Some(refresh_token) => oauth2_client.revoke(refresh_token, "refresh_token").await,
+          None => Ok(())
+        };
+        let access_result: Result<()> = oauth2_client.revoke(access_token, "access_token").await;
+
+        refresh_result.and(access_result)
+      }
     }
   }
 
+  async fn invalidate_token(self: &mut Self) -> Result<()> {
+    let url: Arc<str> = format!(
+      "{base_url}/1.1/oauth/invalidate_token",
+      base_url = self.oauth.base_url
+    ).into();
+
+    self.oauth.request(
+      Method::POST,
+      &url,
+      BTreeMap::new(),
+      None
+    ).await?;
+
+    Ok(())
+  }
+
   pub async fn get_authorization_url(self: &mut Self) -> Result<Arc<str>> {
+    let url: Arc<str> = format!(
+      "{base_url}/oauth/request_token",
+      base_url = self.oauth.base_url
+    ).into();
+
     let token: HashMap<Arc<str>, Arc<str>> = self.oauth.fetch_token(
-      "https://api.twitter.com/oauth/request_token",
-      BTreeMap::new()
+      &url,
+      BTreeMap::from(
+        [
+          ("oauth_callback", get_callback_url().unwrap_or("oob".into()))
+        ]
+      )
     ).await?;
 
     self.request_token = token.get("oauth_token").cloned();
@@ -199,7 +508,8 @@ impl TwitterClient {
 
     Ok(
       format!(
-        "https://api.twitter.com/oauth/authorize?oauth_token={oauth_token}",
+        "{base_url}/oauth/authorize?oauth_token={oauth_token}",
+        base_url = self.oauth.base_url,
         oauth_token = self.request_token
           .clone()
           .ok_or(anyhow!("Get request token failed."))?
@@ -207,6 +517,10 @@ impl TwitterClient {
     )
   }
 
+  pub fn get_request_token(self: &Self) -> Option<Arc<str>> {
+    self.request_token.clone()
+  }
+
   pub async fn get_access_token(
     self: &mut Self,
     verifier: &str
@@ -215,7 +529,8 @@ impl TwitterClient {
       verifier,
       self.request_token
         .as_ref()
-        .ok_or(anyhow!("Get request token failed."))?
+        .ok_or(anyhow!("Get request token failed."))?,
+      self.request_token_secret.clone()
     ).await?;
 
     Ok(
@@ -230,196 +545,179 @@ impl TwitterClient {
     )
   }
 
-  pub async fn like(self: &mut Self, tweet_id: &str) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/favorites/create.json?id={tweet_id}",
-      tweet_id = tweet_id
-    ).into();
+  async fn inspect<T>(self: &Self, result: Result<T>) -> Result<T> {
+    let why: anyhow::Error = match result {
+      Ok(value) => return Ok(value),
+      Err(why) => why
+    };
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    params.insert(
-      "id",
-      tweet_id.into()
-    );
-    
-    self.oauth.request(&url, params).await?;
+    match (self.user_id, &self.access_token) {
+      (Some(user_id), Some(access_token)) if is_revoked(&why, self.oauth.scheme()) => {
+        expire_credential(user_id, access_token).await;
+        Err(why.context(TwitterError::Revoked))
+      },
+      _ => Err(why)
+    }
+  }
 
-    Ok(())
+  pub async fn like(self: &mut Self, tweet_id: &str) -> Result<Like> {
+    let result: Result<Like> = self.api.like(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
   }
 
-  pub async fn unlike(self: &mut Self, tweet_id: &str) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/favorites/destroy.json?id={tweet_id}",
-      tweet_id = tweet_id
-    ).into();
+  pub async fn unlike(self: &mut Self, tweet_id: &str) -> Result<Like> {
+    let result: Result<Like> = self.api.unlike(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
+  }
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    params.insert(
-      "id",
-      tweet_id.into()
-    );
-    
-    self.oauth.request(&url, params).await?;
+  pub async fn retweet(self: &mut Self, tweet_id: &str) -> Result<Retweet> {
+    let result: Result<Retweet> = self.api.retweet(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
+  }
 
-    Ok(())
+  pub async fn unretweet(self: &mut Self, tweet_id: &str) -> Result<Retweet> {
+    let result: Result<Retweet> = self.api.unretweet(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
   }
 
-  pub async fn retweet(self: &mut Self, tweet_id: &str) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/statuses/retweet/{tweet_id}.json",
-      tweet_id = tweet_id
-    ).into();
+  pub async fn bookmark(self: &mut Self, tweet_id: &str) -> Result<Bookmark> {
+    let result: Result<Bookmark> = self.api.bookmark(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
+  }
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
+  pub async fn unbookmark(self: &mut Self, tweet_id: &str) -> Result<Bookmark> {
+    let result: Result<Bookmark> = self.api.unbookmark(&mut self.oauth, tweet_id).await;
+    self.inspect(result).await
+  }
+
+  pub async fn get_tweet(self: &mut Self, tweet_id: &str) -> Result<Tweet> {
+    if let Some(tweet) = TweetCache::get().request(tweet_id).await {
+      return Ok(tweet);
+    }
 
-    self.oauth.request(&url, params).await?;
+    let result: Result<Tweet> = self.api.get_tweet(&mut self.oauth, tweet_id).await;
+    let tweet: Tweet = self.inspect(result).await?;
 
-    Ok(())
+    TweetCache::get().add(tweet.clone()).await;
+
+    Ok(tweet)
   }
 
-  pub async fn unretweet(self: &mut Self, tweet_id: &str) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/statuses/unretweet/{tweet_id}.json",
-      tweet_id = tweet_id
-    ).into();
+  pub async fn get_me(self: &mut Self) -> Result<TwitterUser> {
+    let result: Result<TwitterUser> = self.api.get_me(&mut self.oauth).await;
+    self.inspect(result).await
+  }
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    
-    self.oauth.request(&url, params).await?;
+  pub fn supports(self: &Self, action: TweetAction) -> bool {
+    self.api.supports(&self.oauth, action)
+  }
 
-    Ok(())
+  pub async fn get_tweet_preview(self: &mut Self, tweet_id: &str) -> Result<TweetPreview> {
+    let result: Result<TweetPreview> = self.api.get_tweet_preview(&mut self.oauth, tweet_id).await;
+    let preview: TweetPreview = self.inspect(result).await?;
+
+    TweetCache::get().add(Tweet::from(&preview)).await;
+
+    Ok(preview)
   }
 
   pub async fn get_author_id(
     self: &mut Self,
     tweet_id: &str
   ) -> Result<Arc<str>> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/statuses/lookup.json?id={tweet_id}&trim_user=true",
-      tweet_id = tweet_id
-    ).into();
+    let tweet: Tweet = self.get_tweet(tweet_id).await?;
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    params.insert(
-      "id",
-      tweet_id.into()
-    );
-    params.insert(
-      "trim_user",
-      "true".into()
-    );
+    debug!("Resolved tweet {} | Author: {} | Text: {:?}", tweet.id, tweet.author_id, tweet.text);
 
-    Ok(
-      LOOKUP_USER_ID_REGEX
-        .get_or_init(
-          || {
-            Regex::new(
-              r#"(?ms).*"user":\{"id":(?P<id>[0-9].*),"id_str":"[0-9].*"\}.*"#
-            ).expect("Regex init failed.")
-          }
-        )
-        .replace(
-          &self.oauth.request(&url, params).await?,
-          "$id"
-        )
-        .into()
-    )
+    Ok(tweet.author_id)
   }
 
-  pub async fn follow(self: &mut Self, user_id: &str) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/friendships/create.json?user_id={user_id}",
-      user_id = user_id
-    ).into();
-
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    params.insert(
-      "user_id",
-      user_id.into()
-    );
-    
-    self.oauth.request(&url, params).await?;
-
-    Ok(())
+  pub async fn follow(self: &mut Self, user_id: &str) -> Result<Friendship> {
+    let result: Result<Friendship> = self.api.follow(&mut self.oauth, user_id).await;
+    self.inspect(result).await
   }
 
   pub async fn unfollow(
     self: &mut Self,
     user_id: &str
-  ) -> Result<()> {
-    let url: Arc<str> = format!(
-      "https://api.twitter.com/1.1/friendships/destroy.json?user_id={user_id}",
-      user_id = user_id
-    ).into();
+  ) -> Result<Friendship> {
+    let result: Result<Friendship> = self.api.unfollow(&mut self.oauth, user_id).await;
+    self.inspect(result).await
+  }
 
-    let mut params: BTreeMap<&str, Arc<str>> = BTreeMap::new();
-    params.insert(
-      "oauth_token",
-      self.access_token
-        .clone()
-        .ok_or(anyhow!("Get access token failed."))?
-    );
-    params.insert(
-      "user_id",
-      user_id.into()
-    );
-    
-    self.oauth.request(&url, params).await?;
+  pub async fn reply(self: &mut Self, tweet_id: &str, text: &str) -> Result<Tweet> {
+    let result: Result<Tweet> = self.api.reply(&mut self.oauth, tweet_id, text).await;
+    self.inspect(result).await
+  }
 
-    Ok(())
+  pub async fn quote(self: &mut Self, tweet_id: &str, text: &str) -> Result<Tweet> {
+    let result: Result<Tweet> = self.api.quote(&mut self.oauth, tweet_id, text).await;
+    self.inspect(result).await
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AuthScheme {
+  OAuth1,
+  Bearer,
+  Basic,
+  Public
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum RequestBody {
+  Json(Arc<str>),
+  Form(Vec<(&'static str, Arc<str>)>)
+}
+
+impl RequestBody {
+  fn content_type(self: &Self) -> &'static str {
+    match self {
+      RequestBody::Json(_) => "application/json",
+      RequestBody::Form(_) => "application/x-www-form-urlencoded"
+    }
+  }
+
+  fn encode(self: &Self) -> Arc<str> {
+    match self {
+      RequestBody::Json(body) => body.clone(),
+      RequestBody::Form(params) => params.iter()
+        .map(
+          |(k, v)| {
+            format!(
+              "{key}={value}",
+              key = percent_encode(k),
+              value = percent_encode(v)
+            )
+          }
+        )
+        .collect::<Vec<String>>()
+        .join("&")
+        .into()
+    }
   }
 }
 
 
 #[derive(Debug)]
-struct OAuthSession {
+pub(crate) struct OAuthSession {
+  scheme: AuthScheme,
+  base_url: Arc<str>,
   client_key: Arc<str>,
   client_secret: Arc<str>,
   resource_owner_key: Option<Arc<str>>,
   resource_owner_secret: Option<Arc<str>>,
+  granted_scope: Option<Arc<str>>,
+  access_level: Option<Arc<str>>,
   hmac_sha1: HmacSha1
 }
 
 unsafe impl Send for OAuthSession {}
 
 impl OAuthSession {
-  fn new(
+  pub(crate) fn new(
+    base_url: Arc<str>,
     client_key: Arc<str>,
     client_secret: Arc<str>,
     resource_owner_key: Option<Arc<str>>,
@@ -427,15 +725,19 @@ impl OAuthSession {
   ) -> Result<OAuthSession> {
     Ok(
       OAuthSession {
+        scheme: AuthScheme::OAuth1,
+        base_url,
         client_key,
         client_secret: client_secret.clone(),
         resource_owner_key,
         resource_owner_secret: resource_owner_secret.clone(),
+        granted_scope: None,
+        access_level: None,
         hmac_sha1: HmacSha1::new_from_slice(
           format!(
             "{consumer_secret}&{token_secret}",
-            consumer_secret = urlencoding::encode(&client_secret),
-            token_secret = urlencoding::encode(
+            consumer_secret = percent_encode(&client_secret),
+            token_secret = percent_encode(
               &resource_owner_secret.unwrap_or("".into())
             )
           ).as_bytes()
@@ -444,13 +746,74 @@ impl OAuthSession {
     )
   }
 
+  pub(crate) fn bearer(
+    base_url: Arc<str>,
+    access_token: Arc<str>
+  ) -> Result<OAuthSession> {
+    let mut session: OAuthSession = OAuthSession::new(
+      base_url,
+      "".into(),
+      "".into(),
+      Some(access_token),
+      None
+    )?;
+    session.scheme = AuthScheme::Bearer;
+
+    Ok(session)
+  }
+
+  pub(crate) fn client(
+    base_url: Arc<str>,
+    client_id: Arc<str>,
+    client_secret: Option<Arc<str>>
+  ) -> Result<OAuthSession> {
+    let scheme: AuthScheme = match client_secret {
+      Some(_) => AuthScheme::Basic,
+      None => AuthScheme::Public
+    };
+
+    let mut session: OAuthSession = OAuthSession::new(
+      base_url,
+      client_id,
+      client_secret.unwrap_or("".into()),
+      None,
+      None
+    )?;
+    session.scheme = scheme;
+
+    Ok(session)
+  }
+
+  pub(crate) fn base_url(self: &Self) -> &str {
+    &self.base_url
+  }
+
+  pub(crate) fn scheme(self: &Self) -> AuthScheme {
+    self.scheme
+  }
+
+  pub(crate) fn is_permitted(self: &Self, action: TweetAction) -> bool {
+    match self.scheme {
+      AuthScheme::Bearer => self.granted_scope
+        .as_ref()
+        .is_none_or(|scope: &Arc<str>| scope.split(' ').any(|scope: &str| scope == action.scope())),
+      _ => self.access_level
+        .as_ref()
+        .is_none_or(|access_level: &Arc<str>| access_level.starts_with("read-write"))
+    }
+  }
+
+  pub(crate) fn resource_owner_key(self: &Self) -> Result<Arc<str>> {
+    self.resource_owner_key
+      .clone()
+      .ok_or(anyhow!("Get access token failed."))
+  }
+
   fn get_http_client(self: &Self) -> Client<HttpsConnector, Body> {
     HTTP_CLIENT.get_or_init(
       || {
         Client::builder().build(
-          HttpsConnectorBuilder::new()
-          .with_native_roots()
-          .https_only()
+          OAuthSession::connector_builder()
           .enable_http1()
           .build()
         )
@@ -458,12 +821,26 @@ impl OAuthSession {
     ).clone()
   }
 
+  #[cfg(not(any(test, feature = "mock")))]
+  fn connector_builder() -> HttpsConnectorBuilder<WantsProtocols1> {
+    HttpsConnectorBuilder::new()
+      .with_native_roots()
+      .https_only()
+  }
+
+  #[cfg(any(test, feature = "mock"))]
+  fn connector_builder() -> HttpsConnectorBuilder<WantsProtocols1> {
+    HttpsConnectorBuilder::new()
+      .with_native_roots()
+      .https_or_http()
+  }
+
   pub(self) fn update_hash_key(self: &mut Self) -> Result<()> {
     self.hmac_sha1 = HmacSha1::new_from_slice(
       format!(
         "{consumer_secret}&{token_secret}",
-        consumer_secret = urlencoding::encode(&self.client_secret),
-        token_secret = urlencoding::encode(
+        consumer_secret = percent_encode(&self.client_secret),
+        token_secret = percent_encode(
           &self.resource_owner_secret.clone().unwrap_or("".into())
         )
       ).as_bytes()
@@ -472,35 +849,15 @@ impl OAuthSession {
     Ok(())
   }
 
-  pub(self) fn apply_signture(
+  pub(crate) fn signature(
     self: &mut Self,
+    method: &Method,
     url: &str,
-    params: &mut BTreeMap<&str, Arc<str>>
-  ) {
+    params: &[(Arc<str>, Arc<str>)]
+  ) -> Result<Arc<str>> {
     debug!("Collected params: {:?}", params);
 
-    let normalized_params: Arc<str> = params.iter()
-      .map(
-        |(k, v)| {
-          format!(
-            "{key}={value}",
-            key = urlencoding::encode(k),
-            value = urlencoding::encode(v)
-          ).into()
-        }
-      )
-      .collect::<Vec<Arc<str>>>()
-      .join("&")
-      .into();
-
-    debug!("Normalized params: {:?}", normalized_params);
-
-    let signature_base: Arc<str> = format!(
-      "{method}&{url}&{params}",
-      method = urlencoding::encode("POST"),
-      url = urlencoding::encode(url),
-      params = urlencoding::encode(&normalized_params)
-    ).into();
+    let signature_base: Arc<str> = signature_base_string(method, url, params)?;
 
     debug!("Signature base: {:?}", signature_base);
 
@@ -511,29 +868,26 @@ impl OAuthSession {
 
     debug!("Signature: {:?}", signature);
 
-    params.insert(
-      "oauth_signature",
-      signature
-    );
+    Ok(signature)
   }
 
   pub(self) fn apply_oauth_params(
     self: &mut Self,
     params: &mut BTreeMap<&str, Arc<str>>
   ) -> Result<()> {
-    let mut rng: StdRng = RNG.get_or_init(
-      || StdRng::from_entropy()
-    ).clone();
-
     params.insert(
       "oauth_consumer_key",
       self.client_key.clone()
     );
+    if let Some(resource_owner_key) = &self.resource_owner_key {
+      params.insert(
+        "oauth_token",
+        resource_owner_key.clone()
+      );
+    }
     params.insert(
       "oauth_nonce",
-      (
-        (u64::MAX as f32 * rng.gen::<f32>()) as u64
-      ).to_string().into()
+      generate_nonce()
     );
     params.insert(
       "oauth_timestamp",
@@ -557,17 +911,23 @@ impl OAuthSession {
 
   pub(self) fn build_request(
     self: &Self,
+    method: Method,
     url: &str,
-    params: Option<BTreeMap<&str, Arc<str>>>
+    authorization: Option<Arc<str>>,
+    body: Option<RequestBody>
   ) -> Result<Request<Body>> {
-    let mut builder: Builder = Request::post(url)
+    let encoded_body: Option<Arc<str>> = body.as_ref().map(RequestBody::encode);
+
+    let mut builder: Builder = Request::builder()
+      .method(method)
+      .uri(url)
       .header(
         header::USER_AGENT,
         "Rust@2021/hyper@0.14.26/hyper-rustls@0.24.0"
       )
       .header(
         header::ACCEPT_ENCODING,
-        "gzip"
+        ACCEPT_ENCODING
       )
       .header(
         header::ACCEPT,
@@ -579,76 +939,209 @@ impl OAuthSession {
       )
       .header(
         header::CONTENT_TYPE,
-        "application/json"
+        body.as_ref().map_or("application/json", RequestBody::content_type)
       )
       .header(
         header::CONTENT_LENGTH,
-        0
+        encoded_body.as_ref().map_or(0, |body: &Arc<str>| body.len())
       );
 
-    if let Some(params) = params {
+    if let Some(authorization) = authorization {
       builder = builder.header(
         header::AUTHORIZATION,
-        format!(
-          "OAuth {params}",
-          params = params.into_iter()
-            .map(
-              |(k, v)| {
-                format!(
-                  r#"{key}="{value}""#,
-                  key = urlencoding::encode(k),
-                  value = urlencoding::encode(&v)
-                ).into()
-              }
-            )
-            .collect::<Vec<Arc<str>>>()
-            .join(", ")
-        )
+        authorization.as_ref()
       );
     }
 
-    let request: Request<Body> = builder.body(Body::empty())?;
+    let request: Request<Body> = builder.body(
+      match encoded_body {
+        Some(body) => Body::from(body.to_string()),
+        None => Body::empty()
+      }
+    )?;
 
     debug!("Updated headers: {:?}", request.headers());
 
     Ok(request)
   }
 
-  async fn request(
+  pub(self) fn authorization<'a>(
     self: &mut Self,
-    url: &str,
-    mut params: BTreeMap<&str, Arc<str>>
-  ) -> Result<Arc<str>> {
+    method: &Method,
+    url: &'a str,
+    mut params: BTreeMap<&'a str, Arc<str>>,
+    body: Option<&RequestBody>
+  ) -> Result<Option<Arc<str>>> {
+    match self.scheme {
+      AuthScheme::OAuth1 => (),
+      AuthScheme::Bearer => return Ok(
+        Some(format!("Bearer {token}", token = self.resource_owner_key()?).into())
+      ),
+      AuthScheme::Basic => return Ok(
+        Some(
+          format!(
+            "Basic {credentials}",
+            credentials = general_purpose::STANDARD.encode(
+              format!(
+                "{client_id}:{client_secret}",
+                client_id = self.client_key,
+                client_secret = self.client_secret
+              )
+            )
+          ).into()
+        )
+      ),
+      AuthScheme::Public => return Ok(None)
+    }
+
     self.apply_oauth_params(&mut params)?;
 
-    self.apply_signture(
-      url.split("?").next().ok_or(anyhow!("Split URL failed."))?,
-      &mut params
+    Ok(Some(self.oauth_header(method, url, params, body)?))
+  }
+
+  pub(self) fn oauth_header<'a>(
+    self: &mut Self,
+    method: &Method,
+    url: &'a str,
+    mut params: BTreeMap<&'a str, Arc<str>>,
+    body: Option<&RequestBody>
+  ) -> Result<Arc<str>> {
+    let mut signed_params: Vec<(Arc<str>, Arc<str>)> = params.iter()
+      .map(|(k, v)| (Arc::from(*k), v.clone()))
+      .collect();
+    if let Some(RequestBody::Form(form)) = body {
+      signed_params.extend(
+        form.iter().map(|(k, v)| (Arc::from(*k), v.clone()))
+      );
+    }
+
+    let signature: Arc<str> = self.signature(method, url, &signed_params)?;
+    params.insert("oauth_signature", signature);
+
+    Ok(
+      format!(
+        "OAuth {params}",
+        params = params.into_iter()
+          .map(
+            |(k, v)| {
+              format!(
+                r#"{key}="{value}""#,
+                key = percent_encode(k),
+                value = percent_encode(&v)
+              ).into()
+            }
+          )
+          .collect::<Vec<Arc<str>>>()
+          .join(", ")
+      ).into()
+    )
+  }
+
+  pub(crate) async fn request<'a>(
+    self: &mut Self,
+    method: Method,
+    url: &'a str,
+    params: BTreeMap<&'a str, Arc<str>>,
+    body: Option<RequestBody>
+  ) -> Result<Arc<str>> {
+    let rate_limit_key: RateLimitKey = RateLimitKey::new(
+      self.resource_owner_key.clone().unwrap_or(self.client_key.clone()),
+      &method,
+      url
     );
 
-    debug!("Sending request: {:?}", url);
+    let mut attempt: u32 = 0;
 
-    let response: Response<Body> = self.get_http_client().request(
-      self.build_request(url, Some(params))?
-    ).await?;
+    loop {
+      RateLimiter::get().acquire(&rate_limit_key).await?;
 
-    let content_length: usize = response.headers()
-      .get(header::CONTENT_LENGTH)
-      .ok_or(anyhow!("Get Content-Length failed."))?
-      .to_str()?
-      .parse()?;
+      let authorization: Option<Arc<str>> = self.authorization(&method, url, params.clone(), body.as_ref())?;
 
-    let mut body: String = String::with_capacity(content_length);
+      debug!("Sending request: {:?} | Attempt: {}", url, attempt + 1);
 
-    debug!("Response length: {:?}", content_length);
+      let response: Result<Response<Body>> = match timeout(
+        REQUEST_TIMEOUT,
+        self.get_http_client().request(
+          self.build_request(method.clone(), url, authorization, body.clone())?
+        )
+      ).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(error)) if error.is_connect() && attempt < MAX_RETRIES => {
+          warn!("Connection failed, retrying: {} | Url: {}", error, url);
+          sleep(retry_delay(attempt)).await;
+          attempt += 1;
+          continue;
+        },
+        Ok(Err(error)) => Err(error.into()),
+        Err(elapsed) => Err(elapsed.into())
+      };
+
+      let response: Response<Body> = match response {
+        Ok(response) => response,
+        Err(error) if is_idempotent(&method) && attempt < MAX_RETRIES => {
+          warn!("Request failed, retrying: {} | Url: {}", error, url);
+          sleep(retry_delay(attempt)).await;
+          attempt += 1;
+          continue;
+        },
+        Err(error) => return Err(error)
+      };
+
+      let status: StatusCode = response.status();
+
+      RateLimiter::get().update(&rate_limit_key, status, response.headers());
+
+      if let Some(access_level) = response.headers()
+        .get("x-access-level")
+        .and_then(|access_level: &header::HeaderValue| access_level.to_str().ok()) {
+        self.access_level = Some(access_level.into());
+      }
 
-    GzDecoder::new(
-      &*body::to_bytes(response).await?
-    ).read_to_string(&mut body)?;
+      debug!("Rate limit budget: {:?}", RateLimiter::get().budget(&rate_limit_key));
 
-    debug!("Decoded body: {:?}", body);
+      if is_retryable_status(status) && is_idempotent(&method) && attempt < MAX_RETRIES {
+        warn!("Server responded {}, retrying | Url: {}", status, url);
+        sleep(retry_delay(attempt)).await;
+        attempt += 1;
+        continue;
+      }
 
-    Ok(body.into())
+      let rate_limit_reset: Option<u64> = response.headers()
+        .get("x-rate-limit-reset")
+        .and_then(|reset: &header::HeaderValue| reset.to_str().ok())
+        .and_then(|reset: &str| reset.parse().ok());
+
+      let body: String = match timeout(REQUEST_TIMEOUT, read_body(response)).await {
+        Ok(Ok(body)) => body,
+        Ok(Err(error)) if is_idempotent(&method) && attempt < MAX_RETRIES => {
+          warn!("Read response failed, retrying: {} | Url: {}", error, url);
+          sleep(retry_delay(attempt)).await;
+          attempt += 1;
+          continue;
+        },
+        Err(elapsed) if is_idempotent(&method) && attempt < MAX_RETRIES => {
+          warn!("Read response timed out, retrying: {} | Url: {}", elapsed, url);
+          sleep(retry_delay(attempt)).await;
+          attempt += 1;
+          continue;
+        },
+        Ok(Err(error)) => return Err(error),
+        Err(elapsed) => return Err(elapsed.into())
+      };
+
+      debug!("Decoded body: {:?}", body);
+
+      if !status.is_success() {
+        return Err(
+          TwitterApiError {
+            rate_limit_reset,
+            ..TwitterApiError::from_response(status, &body)
+          }.into()
+        );
+      }
+
+      return Ok(body.into());
+    }
   }
 
   pub(self) fn decode_token(
@@ -674,26 +1167,31 @@ impl OAuthSession {
     Ok(token)
   }
 
-  async fn fetch_token(
+  async fn fetch_token<'a>(
     self: &mut Self,
-    url: &str,
-    params: BTreeMap<&str, Arc<str>>
+    url: &'a str,
+    params: BTreeMap<&'a str, Arc<str>>
   ) -> Result<HashMap<Arc<str>, Arc<str>>> {
-    let raw_token: Arc<str> = self.request(url, params).await?;
+    let raw_token: Arc<str> = self.request(Method::POST, url, params, None).await?;
     self.decode_token(&raw_token)
   }
 
   async fn get_access_token(
     self: &mut Self,
     verifier: &str,
-    request_token: &str
+    request_token: &str,
+    request_token_secret: Option<Arc<str>>
   ) -> Result<(Option<Arc<str>> ,Option<Arc<str>>)> {
     let url: Arc<str> = format!(
-      "https://api.twitter.com/oauth/access_token?oauth_verifier={oauth_verifier}&oauth_token={oauth_token}",
-      oauth_verifier = verifier,
-      oauth_token = request_token
+      "{base_url}/oauth/access_token?oauth_verifier={oauth_verifier}&oauth_token={oauth_token}",
+      base_url = self.base_url,
+      oauth_verifier = percent_encode(verifier),
+      oauth_token = percent_encode(request_token)
     ).into();
 
+    self.resource_owner_secret = request_token_secret;
+    self.update_hash_key()?;
+
     let token: HashMap<Arc<str>, Arc<str>> = self.fetch_token(
       &url,
       BTreeMap::new()
@@ -711,3 +1209,86 @@ impl OAuthSession {
     )
   }
 }
+
+
+#[cfg(test)]
+mod tests {
+  use std::{sync::Arc, collections::BTreeMap};
+
+  use hyper::Method;
+
+  use super::{OAuthSession, RequestBody};
+
+
+  static TWITTER_URL: &str = "https://api.twitter.com/1.1/statuses/update.json?include_entities=true";
+  static TWITTER_STATUS: &str = "Hello Ladies + Gentlemen, a signed OAuth request!";
+  static TWITTER_SIGNATURE: &str = "hCtSmYh+iHYCEqBWrE7C7hYmtUk=";
+
+
+  fn twitter_session() -> OAuthSession {
+    OAuthSession::new(
+      "https://api.twitter.com".into(),
+      "xvz1evFS4wEEPTGEFPHBog".into(),
+      "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw".into(),
+      Some("370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".into()),
+      Some("LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE".into())
+    ).expect("Create session failed.")
+  }
+
+  fn twitter_oauth_params() -> BTreeMap<&'static str, Arc<str>> {
+    BTreeMap::from(
+      [
+        ("oauth_consumer_key", Arc::from("xvz1evFS4wEEPTGEFPHBog")),
+        ("oauth_nonce", Arc::from("kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg")),
+        ("oauth_signature_method", Arc::from("HMAC-SHA1")),
+        ("oauth_timestamp", Arc::from("1318622958")),
+        ("oauth_token", Arc::from("370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb")),
+        ("oauth_version", Arc::from("1.0"))
+      ]
+    )
+  }
+
+
+  #[test]
+  fn twitter_signature_example() {
+    let mut session: OAuthSession = twitter_session();
+
+    let mut params: Vec<(Arc<str>, Arc<str>)> = twitter_oauth_params()
+      .into_iter()
+      .map(|(k, v): (&str, Arc<str>)| (Arc::from(k), v))
+      .collect();
+    params.push((Arc::from("status"), Arc::from(TWITTER_STATUS)));
+
+    for _ in 0..2 {
+      let signature: Arc<str> = session.signature(&Method::POST, TWITTER_URL, &params)
+        .expect("Sign request failed.");
+
+      assert_eq!(signature.as_ref(), TWITTER_SIGNATURE);
+    }
+  }
+
+  #[test]
+  fn twitter_authorization_header_example() {
+    let mut session: OAuthSession = twitter_session();
+
+    let header: Arc<str> = session.oauth_header(
+      &Method::POST,
+      TWITTER_URL,
+      twitter_oauth_params(),
+      Some(&RequestBody::Form(vec![("status", Arc::from(TWITTER_STATUS))]))
+    ).expect("Build authorization header failed.");
+
+    assert_eq!(
+      header.as_ref(),
+      concat!(
+        r#"OAuth oauth_consumer_key="xvz1evFS4wEEPTGEFPHBog", "#,
+        r#"oauth_nonce="kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg", "#,
+        r#"oauth_signature="hCtSmYh%2BiHYCEqBWrE7C7hYmtUk%3D", "#,
+        r#"oauth_signature_method="HMAC-SHA1", "#,
+        r#"oauth_timestamp="1318622958", "#,
+        r#"oauth_token="370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb", "#,
+        r#"oauth_version="1.0""#
+      )
+    );
+  }
+}
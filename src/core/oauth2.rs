@@ -0,0 +1,287 @@
+use std::{
+  collections::BTreeMap,
+  sync::{OnceLock, Arc},
+  time::{SystemTime, UNIX_EPOCH},
+  env
+};
+
+use base64::{engine::general_purpose, Engine};
+use hyper::Method;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use sha2::{Sha256, Digest};
+use anyhow::{Result, anyhow, bail};
+
+use super::{
+  callback::get_callback_url,
+  oauth::{OAuthSession, RequestBody, get_api_base_url},
+  store::Credential
+};
+
+
+pub static DEFAULT_AUTHORIZE_URL: &str = "https://twitter.com/i/oauth2/authorize";
+pub static OAUTH2_SCOPES: [&str; 8] = [
+  "tweet.read",
+  "tweet.write",
+  "users.read",
+  "like.write",
+  "follows.write",
+  "bookmark.read",
+  "bookmark.write",
+  "offline.access"
+];
+pub static REFRESH_MARGIN: u64 = 300;
+
+pub static TWITTER_CLIENT_ID: OnceLock<Arc<str>> = OnceLock::new();
+pub static TWITTER_CLIENT_SECRET: OnceLock<Option<Arc<str>>> = OnceLock::new();
+pub static TWITTER_OAUTH2_REDIRECT_URI: OnceLock<Arc<str>> = OnceLock::new();
+pub static TWITTER_OAUTH2_AUTHORIZE_URL: OnceLock<Arc<str>> = OnceLock::new();
+pub static AUTH_FLOW: OnceLock<AuthFlow> = OnceLock::new();
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuthFlow {
+  OAuth1,
+  OAuth2
+}
+
+pub fn get_auth_flow() -> AuthFlow {
+  *AUTH_FLOW.get_or_init(|| parse_auth_flow().unwrap_or(AuthFlow::OAuth1))
+}
+
+pub fn check_oauth2_config() -> Result<()> {
+  if parse_auth_flow()? == AuthFlow::OAuth2 {
+    get_client_id()?;
+    get_redirect_uri()?;
+  }
+
+  Ok(())
+}
+
+fn parse_auth_flow() -> Result<AuthFlow> {
+  match env::var("TWITTER_AUTH_FLOW").as_deref() {
+    Ok("oauth2") => Ok(AuthFlow::OAuth2),
+    Ok("oauth1") | Err(_) => Ok(AuthFlow::OAuth1),
+    Ok(flow) => bail!("Unsupported TWITTER_AUTH_FLOW `{}`.", flow)
+  }
+}
+
+fn get_client_id() -> Result<Arc<str>> {
+  if let Some(client_id) = TWITTER_CLIENT_ID.get() {
+    return Ok(client_id.clone());
+  }
+
+  let client_id: Arc<str> = env::var("TWITTER_CLIENT_ID")
+    .map_err(|_| anyhow!("TWITTER_CLIENT_ID is not set."))?
+    .into();
+
+  Ok(TWITTER_CLIENT_ID.get_or_init(|| client_id).clone())
+}
+
+fn get_redirect_uri() -> Result<Arc<str>> {
+  if let Some(redirect_uri) = TWITTER_OAUTH2_REDIRECT_URI.get() {
+    return Ok(redirect_uri.clone());
+  }
+
+  let redirect_uri: Arc<str> = env::var("TWITTER_OAUTH2_REDIRECT_URI")
+    .ok()
+    .map(Arc::from)
+    .or(get_callback_url())
+    .ok_or(anyhow!("TWITTER_OAUTH2_REDIRECT_URI is not set."))?;
+
+  Ok(TWITTER_OAUTH2_REDIRECT_URI.get_or_init(|| redirect_uri).clone())
+}
+
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+  access_token: Arc<str>,
+  refresh_token: Option<Arc<str>>,
+  expires_in: u64,
+  #[serde(default)]
+  scope: Option<Arc<str>>
+}
+
+impl TokenResponse {
+  fn into_credential(self: Self) -> Result<Credential> {
+    Ok(
+      Credential::OAuth2 {
+        access_token: self.access_token,
+        refresh_token: self.refresh_token,
+        expires_at: SystemTime::now()
+          .duration_since(UNIX_EPOCH)?
+          .as_secs() + self.expires_in,
+        scope: self.scope
+      }
+    )
+  }
+}
+
+
+#[derive(Debug, Deserialize)]
+struct RevokeResponse {
+  revoked: bool
+}
+
+
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+  pub url: Arc<str>,
+  pub state: Arc<str>,
+  pub code_verifier: Arc<str>
+}
+
+impl AuthorizationRequest {
+  fn random_string(length: usize) -> Arc<str> {
+    rand::thread_rng()
+      .sample_iter(&Alphanumeric)
+      .take(length)
+      .map(char::from)
+      .collect::<String>()
+      .into()
+  }
+
+  fn new(client_id: &str, redirect_uri: &str) -> AuthorizationRequest {
+    let state: Arc<str> = AuthorizationRequest::random_string(32);
+    let code_verifier: Arc<str> = AuthorizationRequest::random_string(64);
+
+    let code_challenge: String = general_purpose::URL_SAFE_NO_PAD.encode(
+      Sha256::digest(code_verifier.as_bytes())
+    );
+
+    let url: Arc<str> = format!(
+      "{authorize_url}?response_type=code&client_id={client_id}&redirect_uri={redirect_uri}&scope={scope}&state={state}&code_challenge={code_challenge}&code_challenge_method=S256",
+      authorize_url = TWITTER_OAUTH2_AUTHORIZE_URL.get_or_init(
+        || {
+          env::var("TWITTER_OAUTH2_AUTHORIZE_URL")
+            .unwrap_or(DEFAULT_AUTHORIZE_URL.to_string())
+            .into()
+        }
+      ),
+      client_id = urlencoding::encode(client_id),
+      redirect_uri = urlencoding::encode(redirect_uri),
+      scope = urlencoding::encode(&OAUTH2_SCOPES.join(" "))
+    ).into();
+
+    AuthorizationRequest {
+      url,
+      state,
+      code_verifier
+    }
+  }
+}
+
+
+#[derive(Debug)]
+pub struct OAuth2Client {
+  client_id: Arc<str>,
+  redirect_uri: Arc<str>,
+  oauth: OAuthSession
+}
+
+unsafe impl Send for OAuth2Client {}
+
+impl OAuth2Client {
+  pub fn new() -> Result<OAuth2Client> {
+    let client_id: Arc<str> = get_client_id()?;
+
+    Ok(
+      OAuth2Client {
+        client_id: client_id.clone(),
+        redirect_uri: get_redirect_uri()?,
+        oauth: OAuthSession::client(
+          get_api_base_url(),
+          client_id,
+          TWITTER_CLIENT_SECRET.get_or_init(
+            || env::var("TWITTER_CLIENT_SECRET").ok().map(Arc::from)
+          ).clone()
+        )?
+      }
+    )
+  }
+
+  pub fn authorization_request(self: &Self) -> AuthorizationRequest {
+    AuthorizationRequest::new(&self.client_id, &self.redirect_uri)
+  }
+
+  async fn request_token(
+    self: &mut Self,
+    mut params: Vec<(&'static str, Arc<str>)>
+  ) -> Result<Credential> {
+    let url: Arc<str> = format!(
+      "{base_url}/2/oauth2/token",
+      base_url = self.oauth.base_url()
+    ).into();
+
+    params.push(("client_id", self.client_id.clone()));
+
+    serde_json::from_str::<TokenResponse>(
+      &self.oauth.request(
+        Method::POST,
+        &url,
+        BTreeMap::new(),
+        Some(RequestBody::Form(params))
+      ).await?
+    )?.into_credential()
+  }
+
+  pub async fn exchange_code(
+    self: &mut Self,
+    code: &str,
+    code_verifier: &str
+  ) -> Result<Credential> {
+    self.request_token(
+      vec![
+        ("grant_type", "authorization_code".into()),
+        ("code", code.into()),
+        ("redirect_uri", self.redirect_uri.clone()),
+        ("code_verifier", code_verifier.into())
+      ]
+    ).await
+  }
+
+  pub async fn refresh(
+    self: &mut Self,
+    refresh_token: &str
+  ) -> Result<Credential> {
+    self.request_token(
+      vec![
+        ("grant_type", "refresh_token".into()),
+        ("refresh_token", refresh_token.into())
+      ]
+    ).await
+  }
+
+  pub async fn revoke(
+    self: &mut Self,
+    token: &str,
+    token_type_hint: &str
+  ) -> Result<()> {
+    let url: Arc<str> = format!(
+      "{base_url}/2/oauth2/revoke",
+      base_url = self.oauth.base_url()
+    ).into();
+
+    let response: RevokeResponse = serde_json::from_str(
+      &self.oauth.request(
+        Method::POST,
+        &url,
+        BTreeMap::new(),
+        Some(
+          RequestBody::Form(
+            vec![
+              ("token", token.into()),
+              ("token_type_hint", token_type_hint.into()),
+              ("client_id", self.client_id.clone())
+            ]
+          )
+        )
+      ).await?
+    )?;
+
+    match response.revoked {
+      true => Ok(()),
+      false => bail!("Revoke token failed.")
+    }
+  }
+}
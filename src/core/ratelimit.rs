@@ -0,0 +1,196 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex, MutexGuard, OnceLock},
+  time::Duration
+};
+
+use hyper::{HeaderMap, Method, StatusCode, Uri};
+use tokio::time::sleep;
+use tracing::log::{debug, warn};
+use anyhow::Result;
+
+use super::{
+  error::TwitterError,
+  utils::current_time
+};
+
+
+pub static MAX_QUEUE_DELAY: u64 = 30;
+pub static DEFAULT_RATE_LIMIT_WINDOW: u64 = 15 * 60;
+
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RateLimitKey {
+  pub token: Arc<str>,
+  pub endpoint: Arc<str>
+}
+
+impl RateLimitKey {
+  pub fn new(token: Arc<str>, method: &Method, url: &str) -> RateLimitKey {
+    let path: String = url.parse::<Uri>()
+      .map(|uri: Uri| uri.path().to_string())
+      .unwrap_or(url.to_string());
+
+    RateLimitKey {
+      token,
+      endpoint: format!(
+        "{method} {path}",
+        path = path
+          .split("/")
+          .enumerate()
+          .map(
+            |(index, segment): (usize, &str)| {
+              let (id, suffix): (&str, &str) = segment
+                .split_once(".")
+                .unwrap_or((segment, ""));
+
+              match index > 1 && !id.is_empty() && id.bytes().all(|byte: u8| byte.is_ascii_digit()) {
+                true if suffix.is_empty() => ":id".to_string(),
+                true => format!(":id.{suffix}"),
+                false => segment.to_string()
+              }
+            }
+          )
+          .collect::<Vec<String>>()
+          .join("/")
+      ).into()
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitBudget {
+  pub limit: u32,
+  pub remaining: u32,
+  pub reset_at: u64
+}
+
+impl RateLimitBudget {
+  fn from_headers(headers: &HeaderMap) -> Option<RateLimitBudget> {
+    let header = |name: &str| -> Option<u64> {
+      headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+    };
+
+    Some(
+      RateLimitBudget {
+        limit: header("x-rate-limit-limit")? as u32,
+        remaining: header("x-rate-limit-remaining")? as u32,
+        reset_at: header("x-rate-limit-reset")?
+      }
+    )
+  }
+}
+
+
+#[derive(Debug)]
+pub struct RateLimiter {
+  budgets: Mutex<HashMap<RateLimitKey, RateLimitBudget>>
+}
+
+impl RateLimiter {
+  pub fn get() -> &'static RateLimiter {
+    RATE_LIMITER.get_or_init(
+      || {
+        RateLimiter {
+          budgets: Mutex::new(HashMap::new())
+        }
+      }
+    )
+  }
+
+  pub fn budget(self: &Self, key: &RateLimitKey) -> Option<RateLimitBudget> {
+    let mut budgets: MutexGuard<HashMap<RateLimitKey, RateLimitBudget>> = self.budgets
+      .lock()
+      .expect("Rate limit budgets poisoned.");
+
+    match budgets.get(key) {
+      Some(budget) if budget.reset_at <= current_time() => {
+        budgets.remove(key);
+        None
+      },
+      Some(budget) => Some(*budget),
+      None => None
+    }
+  }
+
+  fn reserve(self: &Self, key: &RateLimitKey) -> Option<u64> {
+    let mut budgets: MutexGuard<HashMap<RateLimitKey, RateLimitBudget>> = self.budgets
+      .lock()
+      .expect("Rate limit budgets poisoned.");
+
+    match budgets.get_mut(key) {
+      Some(budget) if budget.reset_at <= current_time() => {
+        budgets.remove(key);
+        None
+      },
+      Some(budget) if budget.remaining == 0 => Some(budget.reset_at),
+      Some(budget) => {
+        budget.remaining -= 1;
+        None
+      },
+      None => None
+    }
+  }
+
+  pub async fn acquire(self: &Self, key: &RateLimitKey) -> Result<()> {
+    while let Some(reset_at) = self.reserve(key) {
+      let delay: u64 = reset_at.saturating_sub(current_time());
+
+      if delay > MAX_QUEUE_DELAY {
+        return Err(TwitterError::RateLimited { reset_at: Some(reset_at) }.into());
+      }
+
+      debug!("Rate limited, queued for {}s | Endpoint: {}", delay, key.endpoint);
+
+      sleep(Duration::from_secs(delay.max(1))).await;
+    }
+
+    Ok(())
+  }
+
+  pub fn update(
+    self: &Self,
+    key: &RateLimitKey,
+    status: StatusCode,
+    headers: &HeaderMap
+  ) {
+    let budget: Option<RateLimitBudget> = match (RateLimitBudget::from_headers(headers), status) {
+      (Some(budget), StatusCode::TOO_MANY_REQUESTS) => Some(
+        RateLimitBudget {
+          remaining: 0,
+          ..budget
+        }
+      ),
+      (None, StatusCode::TOO_MANY_REQUESTS) => Some(
+        RateLimitBudget {
+          limit: 0,
+          remaining: 0,
+          reset_at: current_time() + DEFAULT_RATE_LIMIT_WINDOW
+        }
+      ),
+      (budget, _) => budget
+    };
+
+    if let Some(budget) = budget {
+      if budget.remaining == 0 {
+        warn!("Rate limit exhausted until {} | Endpoint: {}", budget.reset_at, key.endpoint);
+      }
+
+      let mut budgets: MutexGuard<HashMap<RateLimitKey, RateLimitBudget>> = self.budgets
+        .lock()
+        .expect("Rate limit budgets poisoned.");
+
+      let current_time: u64 = current_time();
+      budgets.retain(|_, budget: &mut RateLimitBudget| budget.reset_at > current_time);
+      budgets.insert(key.clone(), budget);
+    }
+  }
+}
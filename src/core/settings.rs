@@ -0,0 +1,208 @@
+use std::{
+  sync::OnceLock,
+  collections::{HashMap, BTreeSet}
+};
+
+use serde::{Serialize, Deserialize};
+use serenity::model::id::GuildId;
+use tokio::sync::RwLock;
+use tracing::log::error;
+use anyhow::Result;
+
+use super::{
+  store::get_database,
+  action::TweetAction,
+  link::LinkScope,
+  lock::{KeyedLock, KeyedGuard}
+};
+
+
+pub static GUILD_SETTINGS: OnceLock<GuildSettingsStore> = OnceLock::new();
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionMode {
+  #[default]
+  Reactions,
+  Buttons
+}
+
+impl ActionMode {
+  pub fn from_name(name: &str) -> Option<ActionMode> {
+    match name {
+      "reactions" => Some(ActionMode::Reactions),
+      "buttons" => Some(ActionMode::Buttons),
+      _ => None
+    }
+  }
+
+  pub fn name(self: &Self) -> &'static str {
+    match self {
+      ActionMode::Reactions => "reactions",
+      ActionMode::Buttons => "buttons"
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelFilter {
+  #[default]
+  Denylist,
+  Allowlist
+}
+
+impl ChannelFilter {
+  pub fn from_name(name: &str) -> Option<ChannelFilter> {
+    match name {
+      "denylist" => Some(ChannelFilter::Denylist),
+      "allowlist" => Some(ChannelFilter::Allowlist),
+      _ => None
+    }
+  }
+
+  pub fn name(self: &Self) -> &'static str {
+    match self {
+      ChannelFilter::Denylist => "denylist",
+      ChannelFilter::Allowlist => "allowlist"
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+  pub mode: ActionMode,
+  pub auto_reactions: bool,
+  pub previews: bool,
+  pub disabled_actions: BTreeSet<TweetAction>,
+  pub channel_filter: ChannelFilter,
+  pub channels: BTreeSet<u64>,
+  pub quoted_links: bool,
+  pub spoiler_links: bool
+}
+
+impl Default for GuildSettings {
+  fn default() -> GuildSettings {
+    GuildSettings {
+      mode: ActionMode::default(),
+      auto_reactions: true,
+      previews: false,
+      disabled_actions: BTreeSet::new(),
+      channel_filter: ChannelFilter::default(),
+      channels: BTreeSet::new(),
+      quoted_links: false,
+      spoiler_links: true
+    }
+  }
+}
+
+impl GuildSettings {
+  pub fn is_channel_allowed(self: &Self, channel_id: u64) -> bool {
+    match self.channel_filter {
+      ChannelFilter::Denylist => !self.channels.contains(&channel_id),
+      ChannelFilter::Allowlist => self.channels.contains(&channel_id)
+    }
+  }
+
+  pub fn is_action_enabled(self: &Self, action: TweetAction) -> bool {
+    action.is_available() && !self.disabled_actions.contains(&action)
+  }
+
+  pub fn link_scope(self: &Self) -> LinkScope {
+    LinkScope {
+      quotes: self.quoted_links,
+      spoilers: self.spoiler_links
+    }
+  }
+
+  pub fn enabled_actions(self: &Self) -> Vec<TweetAction> {
+    TweetAction::ALL
+      .into_iter()
+      .filter(|action: &TweetAction| self.is_action_enabled(*action))
+      .collect()
+  }
+}
+
+
+#[derive(Debug)]
+pub struct GuildSettingsStore {
+  tree: sled::Tree,
+  data: RwLock<HashMap<u64, GuildSettings>>,
+  locks: KeyedLock<u64>
+}
+
+impl GuildSettingsStore {
+  pub fn get() -> &'static GuildSettingsStore {
+    GUILD_SETTINGS.get_or_init(
+      || {
+        GuildSettingsStore {
+          tree: get_database()
+            .open_tree("guild_settings")
+            .expect("Open guild settings failed."),
+          data: RwLock::new(HashMap::new()),
+          locks: KeyedLock::new()
+        }
+      }
+    )
+  }
+
+  pub async fn lock(self: &Self, guild_id: u64) -> KeyedGuard<'_, u64> {
+    self.locks.lock(guild_id).await
+  }
+
+  pub async fn load(self: &Self, guild_id: u64) -> Result<GuildSettings> {
+    if let Some(settings) = self.data.read().await.get(&guild_id) {
+      return Ok(settings.clone());
+    }
+
+    let settings: GuildSettings = match self.tree.get(guild_id.to_be_bytes())? {
+      Some(raw_settings) => serde_json::from_slice(&raw_settings)?,
+      None => GuildSettings::default()
+    };
+
+    self.data
+      .write()
+      .await
+      .insert(guild_id, settings.clone());
+
+    Ok(settings)
+  }
+
+  pub async fn resolve(self: &Self, guild_id: Option<GuildId>) -> GuildSettings {
+    let guild_id: u64 = match guild_id {
+      Some(guild_id) => *guild_id.as_u64(),
+      None => return GuildSettings::default()
+    };
+
+    match self.load(guild_id).await {
+      Ok(settings) => settings,
+      Err(why) => {
+        error!("Load guild settings error: {:?} | Guild: {}", why, guild_id);
+        GuildSettings::default()
+      }
+    }
+  }
+
+  pub async fn save(
+    self: &Self,
+    guild_id: u64,
+    settings: GuildSettings
+  ) -> Result<()> {
+    self.tree.insert(
+      guild_id.to_be_bytes(),
+      serde_json::to_vec(&settings)?
+    )?;
+    self.tree.flush()?;
+
+    self.data
+      .write()
+      .await
+      .insert(guild_id, settings);
+
+    Ok(())
+  }
+}
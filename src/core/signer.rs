@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use hyper::{Method, Uri};
+use itertools::Itertools;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use anyhow::{Result, anyhow};
+
+
+static NONCE_LENGTH: usize = 32;
+
+
+pub fn percent_encode(value: &str) -> String {
+  let mut encoded: String = String::with_capacity(value.len());
+
+  for byte in value.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+      _ => encoded.push_str(&format!("%{:02X}", byte))
+    }
+  }
+
+  encoded
+}
+
+pub fn parse_form(form: &str) -> Result<Vec<(Arc<str>, Arc<str>)>> {
+  let mut params: Vec<(Arc<str>, Arc<str>)> = Vec::new();
+
+  for pair in form.split("&").filter(|pair: &&str| !pair.is_empty()) {
+    let (k, v): (&str, &str) = pair.split_once("=").unwrap_or((pair, ""));
+
+    params.push(
+      (
+        urlencoding::decode(&k.replace("+", " "))?.into(),
+        urlencoding::decode(&v.replace("+", " "))?.into()
+      )
+    );
+  }
+
+  Ok(params)
+}
+
+pub fn generate_nonce() -> Arc<str> {
+  OsRng
+    .sample_iter(&Alphanumeric)
+    .take(NONCE_LENGTH)
+    .map(char::from)
+    .collect::<String>()
+    .into()
+}
+
+
+fn base_string_uri(uri: &Uri) -> Result<Arc<str>> {
+  let scheme: String = uri.scheme_str()
+    .ok_or(anyhow!("Missing URL scheme."))?
+    .to_ascii_lowercase();
+  let host: String = uri.host()
+    .ok_or(anyhow!("Missing URL host."))?
+    .to_ascii_lowercase();
+
+  let port: String = match (scheme.as_str(), uri.port_u16()) {
+    ("http", Some(80)) | ("https", Some(443)) | (_, None) => "".to_string(),
+    (_, Some(port)) => format!(":{port}")
+  };
+
+  Ok(
+    format!(
+      "{scheme}://{host}{port}{path}",
+      path = uri.path()
+    ).into()
+  )
+}
+
+fn normalize_parameters(params: &[(Arc<str>, Arc<str>)]) -> Arc<str> {
+  params.iter()
+    .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+    .sorted()
+    .map(|(k, v)| format!("{k}={v}"))
+    .collect::<Vec<String>>()
+    .join("&")
+    .into()
+}
+
+pub fn signature_base_string(
+  method: &Method,
+  url: &str,
+  params: &[(Arc<str>, Arc<str>)]
+) -> Result<Arc<str>> {
+  let uri: Uri = url.parse()?;
+
+  let mut collected_params: Vec<(Arc<str>, Arc<str>)> = parse_form(uri.query().unwrap_or(""))?;
+  collected_params.extend(
+    params.iter()
+      .filter(|(k, _)| k.as_ref() != "oauth_signature" && k.as_ref() != "realm")
+      .cloned()
+  );
+
+  Ok(
+    format!(
+      "{method}&{uri}&{params}",
+      method = percent_encode(&method.as_str().to_ascii_uppercase()),
+      uri = percent_encode(&base_string_uri(&uri)?),
+      params = percent_encode(&normalize_parameters(&collected_params))
+    ).into()
+  )
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use hyper::Method;
+
+  use super::{percent_encode, signature_base_string};
+
+
+  fn params(pairs: &[(&str, &str)]) -> Vec<(Arc<str>, Arc<str>)> {
+    pairs
+      .iter()
+      .map(|(k, v): &(&str, &str)| (Arc::from(*k), Arc::from(*v)))
+      .collect()
+  }
+
+
+  #[test]
+  fn rfc5849_base_string() {
+    let signature_base: Arc<str> = signature_base_string(
+      &Method::POST,
+      "http://example.com/request?b5=%3D%253D&a3=a&c%40=&a2=r%20b",
+      &params(
+        &[
+          ("realm", "Example"),
+          ("oauth_consumer_key", "9djdj82h48djs9d2"),
+          ("oauth_token", "kkk9d7dh3k39sjv7"),
+          ("oauth_signature_method", "HMAC-SHA1"),
+          ("oauth_timestamp", "137131201"),
+          ("oauth_nonce", "7d8f3e4a"),
+          ("oauth_signature", "bYT5CMsGcbgUdFHObYMEfcx6bsw="),
+          ("c2", ""),
+          ("a3", "2 q")
+        ]
+      )
+    ).expect("Build signature base failed.");
+
+    assert_eq!(
+      signature_base.as_ref(),
+      "POST&http%3A%2F%2Fexample.com%2Frequest&a2%3Dr%2520b%26a3%3D2%2520q%26a3%3Da%26b5%3D%253D%25253D%26c%2540%3D%26c2%3D%26oauth_consumer_key%3D9djdj82h48djs9d2%26oauth_nonce%3D7d8f3e4a%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D137131201%26oauth_token%3Dkkk9d7dh3k39sjv7"
+    );
+  }
+
+  #[test]
+  fn twitter_base_string() {
+    let signature_base: Arc<str> = signature_base_string(
+      &Method::POST,
+      "https://api.twitter.com/1.1/statuses/update.json?include_entities=true",
+      &params(
+        &[
+          ("status", "Hello Ladies + Gentlemen, a signed OAuth request!"),
+          ("oauth_consumer_key", "xvz1evFS4wEEPTGEFPHBog"),
+          ("oauth_nonce", "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg"),
+          ("oauth_signature_method", "HMAC-SHA1"),
+          ("oauth_timestamp", "1318622958"),
+          ("oauth_token", "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb"),
+          ("oauth_version", "1.0")
+        ]
+      )
+    ).expect("Build signature base failed.");
+
+    assert_eq!(
+      signature_base.as_ref(),
+      "POST&https%3A%2F%2Fapi.twitter.com%2F1.1%2Fstatuses%2Fupdate.json&include_entities%3Dtrue%26oauth_consumer_key%3Dxvz1evFS4wEEPTGEFPHBog%26oauth_nonce%3DkYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D1318622958%26oauth_token%3D370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb%26oauth_version%3D1.0%26status%3DHello%2520Ladies%2520%252B%2520Gentlemen%252C%2520a%2520signed%2520OAuth%2520request%2521"
+    );
+  }
+
+  #[test]
+  fn percent_encode_edge_cases() {
+    assert_eq!(percent_encode("AZaz09-._~"), "AZaz09-._~");
+    assert_eq!(percent_encode("!*'();:@&=+$,/?#[]"), "%21%2A%27%28%29%3B%3A%40%26%3D%2B%24%2C%2F%3F%23%5B%5D");
+    assert_eq!(percent_encode(" %"), "%20%25");
+    assert_eq!(percent_encode("é☃"), "%C3%A9%E2%98%83");
+    assert_eq!(percent_encode(""), "");
+  }
+}
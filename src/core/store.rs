@@ -0,0 +1,389 @@
+use std::{
+  sync::{Arc, OnceLock},
+  collections::BTreeMap,
+  env,
+  fmt::Debug,
+  time::{SystemTime, UNIX_EPOCH}
+};
+
+use base64::{engine::general_purpose, Engine};
+use chacha20poly1305::{
+  aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+  ChaCha20Poly1305,
+  Key,
+  Nonce
+};
+use serde::{Serialize, Deserialize};
+use rand::{distributions::Alphanumeric, Rng};
+use anyhow::{Result, anyhow, bail};
+
+
+pub static DEFAULT_DATABASE_PATH: &str = "data/twitcord";
+pub static UNIDENTIFIED_ACCOUNT_PREFIX: &str = "unidentified:";
+
+static NONCE_LENGTH: usize = 12;
+static DATABASE: OnceLock<sled::Db> = OnceLock::new();
+
+
+pub fn get_database() -> &'static sled::Db {
+  DATABASE.get_or_init(
+    || {
+      sled::open(
+        env::var("DATABASE_PATH").unwrap_or(DEFAULT_DATABASE_PATH.to_string())
+      ).expect("Open database failed.")
+    }
+  )
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Credential {
+  OAuth1 {
+    access_token: Arc<str>,
+    access_token_secret: Arc<str>
+  },
+  OAuth2 {
+    access_token: Arc<str>,
+    refresh_token: Option<Arc<str>>,
+    expires_at: u64,
+    #[serde(default)]
+    scope: Option<Arc<str>>
+  }
+}
+
+impl Credential {
+  pub fn access_token(self: &Self) -> &Arc<str> {
+    match self {
+      Credential::OAuth1 { access_token, .. } | Credential::OAuth2 { access_token, .. } => access_token
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+  #[serde(flatten)]
+  pub credential: Credential,
+  #[serde(default)]
+  pub account_id: Arc<str>,
+  #[serde(default)]
+  pub screen_name: Arc<str>,
+  #[serde(default)]
+  pub locale: Arc<str>,
+  pub created_at: u64
+}
+
+impl StoredToken {
+  pub fn new(
+    credential: Credential,
+    account_id: Arc<str>,
+    screen_name: Arc<str>,
+    locale: Arc<str>
+  ) -> StoredToken {
+    StoredToken {
+      credential,
+      account_id,
+      screen_name,
+      locale,
+      created_at: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Epoch fail!")
+        .as_secs()
+    }
+  }
+
+  pub fn unidentified(credential: Credential, locale: Arc<str>) -> StoredToken {
+    let placeholder: String = rand::thread_rng()
+      .sample_iter(&Alphanumeric)
+      .take(16)
+      .map(char::from)
+      .collect();
+
+    StoredToken::new(
+      credential,
+      format!("{UNIDENTIFIED_ACCOUNT_PREFIX}{placeholder}").into(),
+      "".into(),
+      locale
+    )
+  }
+
+  pub fn is_identified(self: &Self) -> bool {
+    !self.account_id.is_empty() && !self.account_id.starts_with(UNIDENTIFIED_ACCOUNT_PREFIX)
+  }
+}
+
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoredAccounts {
+  pub tokens: Vec<StoredToken>,
+  #[serde(default)]
+  pub active: Option<Arc<str>>,
+  #[serde(default)]
+  pub guild_accounts: BTreeMap<u64, Arc<str>>
+}
+
+impl StoredAccounts {
+  pub fn find(self: &Self, account_id: &str) -> Option<&StoredToken> {
+    self.tokens
+      .iter()
+      .find(|token: &&StoredToken| token.account_id.as_ref() == account_id)
+  }
+
+  pub fn find_by_name(self: &Self, name: &str) -> Option<&StoredToken> {
+    let name: &str = name.trim().trim_start_matches('@');
+
+    if name.is_empty() {
+      return None;
+    }
+
+    self.tokens
+      .iter()
+      .find(
+        |token: &&StoredToken| {
+          token.account_id.as_ref() == name || token.screen_name.eq_ignore_ascii_case(name)
+        }
+      )
+  }
+
+  pub fn select(self: &Self, guild_id: Option<u64>) -> Option<&StoredToken> {
+    guild_id
+      .and_then(|guild_id: u64| self.guild_accounts.get(&guild_id))
+      .into_iter()
+      .chain(&self.active)
+      .find_map(|account_id: &Arc<str>| self.find(account_id))
+      .or(self.tokens.first())
+  }
+
+  pub fn insert(self: &mut Self, token: StoredToken) {
+    self.active = Some(token.account_id.clone());
+
+    match self.tokens.iter_mut().find(
+      |stored_token: &&mut StoredToken| stored_token.account_id == token.account_id
+    ) {
+      Some(stored_token) => *stored_token = token,
+      None => self.tokens.push(token)
+    }
+  }
+
+  pub fn update_credential(self: &mut Self, account_id: &str, credential: Credential) {
+    if let Some(token) = self.tokens.iter_mut().find(
+      |token: &&mut StoredToken| token.account_id.as_ref() == account_id
+    ) {
+      token.credential = credential;
+    }
+  }
+
+  pub fn identify(self: &mut Self, placeholder: &str, account_id: Arc<str>, screen_name: Arc<str>) {
+    let index: usize = match self.tokens.iter().position(
+      |token: &StoredToken| !token.is_identified() && token.account_id.as_ref() == placeholder
+    ) {
+      Some(index) => index,
+      None => return
+    };
+
+    match self.find(&account_id).is_some() {
+      true => {
+        self.tokens.remove(index);
+      },
+      false => {
+        self.tokens[index].account_id = account_id.clone();
+        self.tokens[index].screen_name = screen_name;
+      }
+    }
+
+    if self.active.as_deref() == Some(placeholder) {
+      self.active = Some(account_id.clone());
+    }
+
+    self.guild_accounts
+      .values_mut()
+      .filter(|guild_account: &&mut Arc<str>| guild_account.as_ref() == placeholder)
+      .for_each(|guild_account: &mut Arc<str>| *guild_account = account_id.clone());
+  }
+
+  pub fn remove_credential(self: &mut Self, access_token: &str) -> Option<StoredToken> {
+    let index: usize = self.tokens.iter().position(
+      |token: &StoredToken| token.credential.access_token().as_ref() == access_token
+    )?;
+    let token: StoredToken = self.tokens.remove(index);
+
+    if self.active.as_ref() == Some(&token.account_id) {
+      self.active = None;
+    }
+
+    self.guild_accounts.retain(|_, account_id: &mut Arc<str>| *account_id != token.account_id);
+
+    Some(token)
+  }
+
+  pub fn use_account(self: &mut Self, account_id: Arc<str>, guild_id: Option<u64>) {
+    match guild_id {
+      Some(guild_id) => {
+        self.guild_accounts.insert(guild_id, account_id);
+      },
+      None => self.active = Some(account_id)
+    }
+  }
+}
+
+
+impl From<StoredToken> for StoredAccounts {
+  fn from(token: StoredToken) -> StoredAccounts {
+    StoredAccounts {
+      tokens: vec![token],
+      ..Default::default()
+    }
+  }
+}
+
+
+pub trait TokenStore: Debug + Send + Sync {
+  fn load(self: &Self, user_id: u64) -> Result<Option<StoredAccounts>>;
+
+  fn save(self: &Self, user_id: u64, accounts: &StoredAccounts) -> Result<()>;
+
+  fn remove(self: &Self, user_id: u64) -> Result<()>;
+
+  fn user_ids(self: &Self) -> Result<Vec<u64>>;
+}
+
+
+pub struct TokenCipher {
+  cipher: ChaCha20Poly1305
+}
+
+impl Debug for TokenCipher {
+  fn fmt(self: &Self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("TokenCipher").finish_non_exhaustive()
+  }
+}
+
+impl TokenCipher {
+  pub fn new(key: &[u8]) -> Result<TokenCipher> {
+    if key.len() != 32 {
+      bail!("Token store key must be 32 bytes, got {}.", key.len());
+    }
+
+    Ok(
+      TokenCipher {
+        cipher: ChaCha20Poly1305::new(Key::from_slice(key))
+      }
+    )
+  }
+
+  pub fn from_env() -> Result<TokenCipher> {
+    let encoded_key: String = env::var("TOKEN_STORE_KEY")
+      .map_err(|_| anyhow!("TOKEN_STORE_KEY is not set."))?;
+
+    TokenCipher::new(
+      &general_purpose::STANDARD.decode(encoded_key.trim())?
+    )
+  }
+
+  pub fn encrypt(self: &Self, user_id: u64, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce: Nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let ciphertext: Vec<u8> = self.cipher.encrypt(
+      &nonce,
+      Payload {
+        msg: plaintext,
+        aad: &user_id.to_be_bytes()
+      }
+    ).map_err(|_| anyhow!("Encrypt token failed."))?;
+
+    let mut sealed: Vec<u8> = Vec::with_capacity(NONCE_LENGTH + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+
+    Ok(sealed)
+  }
+
+  pub fn decrypt(self: &Self, user_id: u64, sealed: &[u8]) -> Result<Vec<u8>> {
+    if sealed.len() < NONCE_LENGTH {
+      bail!("Sealed token too short.");
+    }
+
+    let (nonce, ciphertext): (&[u8], &[u8]) = sealed.split_at(NONCE_LENGTH);
+
+    self.cipher.decrypt(
+      Nonce::from_slice(nonce),
+      Payload {
+        msg: ciphertext,
+        aad: &user_id.to_be_bytes()
+      }
+    ).map_err(|_| anyhow!("Decrypt token failed."))
+  }
+}
+
+
+#[derive(Debug)]
+pub struct SledTokenStore {
+  tokens: sled::Tree,
+  cipher: TokenCipher
+}
+
+impl SledTokenStore {
+  pub fn new(db: &sled::Db, cipher: TokenCipher) -> Result<SledTokenStore> {
+    Ok(
+      SledTokenStore {
+        tokens: db.open_tree("access_tokens")?,
+        cipher
+      }
+    )
+  }
+
+  pub fn from_env() -> Result<SledTokenStore> {
+    SledTokenStore::new(
+      get_database(),
+      TokenCipher::from_env()?
+    )
+  }
+}
+
+impl TokenStore for SledTokenStore {
+  fn load(self: &Self, user_id: u64) -> Result<Option<StoredAccounts>> {
+    match self.tokens.get(user_id.to_be_bytes())? {
+      Some(sealed) => {
+        let plaintext: Vec<u8> = self.cipher.decrypt(user_id, &sealed)?;
+
+        match serde_json::from_slice::<StoredAccounts>(&plaintext) {
+          Ok(accounts) => Ok(Some(accounts)),
+          Err(_) => Ok(Some(serde_json::from_slice::<StoredToken>(&plaintext)?.into()))
+        }
+      },
+      None => Ok(None)
+    }
+  }
+
+  fn save(self: &Self, user_id: u64, accounts: &StoredAccounts) -> Result<()> {
+    self.tokens.insert(
+      user_id.to_be_bytes(),
+      self.cipher.encrypt(
+        user_id,
+        &serde_json::to_vec(accounts)?
+      )?
+    )?;
+    self.tokens.flush()?;
+
+    Ok(())
+  }
+
+  fn remove(self: &Self, user_id: u64) -> Result<()> {
+    self.tokens.remove(user_id.to_be_bytes())?;
+    self.tokens.flush()?;
+
+    Ok(())
+  }
+
+  fn user_ids(self: &Self) -> Result<Vec<u64>> {
+    self.tokens
+      .iter()
+      .keys()
+      .map(
+        |key: sled::Result<sled::IVec>| -> Result<u64> {
+          Ok(u64::from_be_bytes(key?.as_ref().try_into()?))
+        }
+      )
+      .collect()
+  }
+}
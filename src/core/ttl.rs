@@ -0,0 +1,268 @@
+use std::{
+  collections::{HashMap, BTreeMap},
+  hash::Hash
+};
+
+use super::utils::current_time;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+  pub size: usize,
+  pub hits: u64,
+  pub misses: u64,
+  pub evictions: u64,
+  pub expirations: u64
+}
+
+#[derive(Debug)]
+struct CacheEntry<V> {
+  value: V,
+  accessed_at: u64,
+  recency: u64
+}
+
+#[derive(Debug)]
+pub struct TtlCache<K, V> {
+  entries: HashMap<K, CacheEntry<V>>,
+  order: BTreeMap<u64, K>,
+  clock: u64,
+  max_age: u64,
+  max_item: usize,
+  stats: CacheStats
+}
+
+impl<K: Eq + Hash + Clone, V> TtlCache<K, V> {
+  pub fn new(max_age: u64, max_item: usize) -> TtlCache<K, V> {
+    TtlCache {
+      entries: HashMap::with_capacity(max_item),
+      order: BTreeMap::new(),
+      clock: 0,
+      max_age,
+      max_item,
+      stats: CacheStats::default()
+    }
+  }
+
+  pub fn get(self: &mut Self, key: &K) -> Option<&V> {
+    let current_time: u64 = current_time();
+
+    if self.is_expired(key, current_time) {
+      self.expire(key);
+    }
+
+    let recency: u64 = self.tick();
+
+    match self.entries.get_mut(key) {
+      Some(entry) => {
+        self.stats.hits += 1;
+        self.order.remove(&entry.recency);
+        self.order.insert(recency, key.clone());
+
+        entry.accessed_at = current_time;
+        entry.recency = recency;
+
+        Some(&entry.value)
+      },
+      None => {
+        self.stats.misses += 1;
+        None
+      }
+    }
+  }
+
+  pub fn peek(self: &Self, key: &K) -> Option<&V> {
+    match self.is_expired(key, current_time()) {
+      true => None,
+      false => self.entries
+        .get(key)
+        .map(|entry: &CacheEntry<V>| &entry.value)
+    }
+  }
+
+  pub fn insert(self: &mut Self, key: K, value: V) {
+    self.remove(&key);
+
+    while self.entries.len() >= self.max_item {
+      let (_, oldest): (u64, K) = match self.order.pop_first() {
+        Some(oldest) => oldest,
+        None => break
+      };
+
+      self.entries.remove(&oldest);
+      self.stats.evictions += 1;
+    }
+
+    if self.max_item == 0 {
+      return;
+    }
+
+    let recency: u64 = self.tick();
+
+    self.order.insert(recency, key.clone());
+    self.entries.insert(
+      key,
+      CacheEntry {
+        value,
+        accessed_at: current_time(),
+        recency
+      }
+    );
+  }
+
+  pub fn remove(self: &mut Self, key: &K) -> Option<V> {
+    let entry: CacheEntry<V> = self.entries.remove(key)?;
+    self.order.remove(&entry.recency);
+
+    Some(entry.value)
+  }
+
+  pub fn clean_up(self: &mut Self) -> usize {
+    let current_time: u64 = current_time();
+
+    let expired: Vec<K> = self.entries
+      .iter()
+      .filter(|(_, entry)| current_time.saturating_sub(entry.accessed_at) > self.max_age)
+      .map(|(key, _)| key.clone())
+      .collect();
+
+    for key in &expired {
+      self.expire(key);
+    }
+
+    self.entries.shrink_to(self.max_item);
+
+    expired.len()
+  }
+
+  pub fn stats(self: &Self) -> CacheStats {
+    CacheStats {
+      size: self.entries.len(),
+      ..self.stats
+    }
+  }
+
+  fn is_expired(self: &Self, key: &K, current_time: u64) -> bool {
+    self.entries
+      .get(key)
+      .is_some_and(|entry: &CacheEntry<V>| current_time.saturating_sub(entry.accessed_at) > self.max_age)
+  }
+
+  fn expire(self: &mut Self, key: &K) {
+    if self.remove(key).is_some() {
+      self.stats.expirations += 1;
+    }
+  }
+
+  fn tick(self: &mut Self) -> u64 {
+    self.clock += 1;
+    self.clock
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::{TtlCache, CacheStats, current_time};
+
+
+  fn age(cache: &mut TtlCache<&'static str, u32>, key: &'static str, seconds: u64) {
+    if let Some(entry) = cache.entries.get_mut(&key) {
+      entry.accessed_at = current_time() - seconds;
+    }
+  }
+
+
+  #[test]
+  fn evicts_least_recently_used() {
+    let mut cache: TtlCache<&str, u32> = TtlCache::new(60, 2);
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+    cache.get(&"a");
+    cache.insert("c", 3);
+
+    assert_eq!(cache.peek(&"a"), Some(&1));
+    assert_eq!(cache.peek(&"b"), None);
+    assert_eq!(cache.peek(&"c"), Some(&3));
+
+    cache.insert("d", 4);
+
+    assert_eq!(cache.peek(&"a"), None);
+    assert_eq!(cache.peek(&"c"), Some(&3));
+    assert_eq!(cache.peek(&"d"), Some(&4));
+  }
+
+  #[test]
+  fn get_slides_expiry() {
+    let mut cache: TtlCache<&str, u32> = TtlCache::new(60, 2);
+    cache.insert("a", 1);
+
+    age(&mut cache, "a", 60);
+    assert_eq!(cache.get(&"a"), Some(&1));
+
+    age(&mut cache, "a", 60);
+    assert_eq!(cache.get(&"a"), Some(&1));
+
+    age(&mut cache, "a", 61);
+    assert_eq!(cache.get(&"a"), None);
+  }
+
+  #[test]
+  fn peek_keeps_recency() {
+    let mut cache: TtlCache<&str, u32> = TtlCache::new(60, 2);
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+
+    age(&mut cache, "a", 30);
+    assert_eq!(cache.peek(&"a"), Some(&1));
+    assert!(cache.entries[&"a"].accessed_at <= current_time() - 30);
+
+    cache.insert("c", 3);
+
+    assert_eq!(cache.peek(&"a"), None);
+    assert_eq!(cache.peek(&"b"), Some(&2));
+  }
+
+  #[test]
+  fn future_access_does_not_expire() {
+    let mut cache: TtlCache<&str, u32> = TtlCache::new(60, 2);
+    cache.insert("a", 1);
+
+    if let Some(entry) = cache.entries.get_mut(&"a") {
+      entry.accessed_at = current_time() + 3600;
+    }
+
+    assert_eq!(cache.peek(&"a"), Some(&1));
+    assert_eq!(cache.get(&"a"), Some(&1));
+    assert_eq!(cache.clean_up(), 0);
+  }
+
+  #[test]
+  fn counts_hits_misses_evictions_and_expirations() {
+    let mut cache: TtlCache<&str, u32> = TtlCache::new(60, 2);
+    cache.insert("a", 1);
+    cache.insert("b", 2);
+    cache.insert("c", 3);
+
+    cache.get(&"a");
+    cache.get(&"b");
+    cache.get(&"c");
+
+    age(&mut cache, "b", 61);
+    cache.get(&"b");
+
+    age(&mut cache, "c", 61);
+    assert_eq!(cache.clean_up(), 1);
+
+    assert_eq!(
+      cache.stats(),
+      CacheStats {
+        size: 0,
+        hits: 2,
+        misses: 2,
+        evictions: 1,
+        expirations: 2
+      }
+    );
+  }
+}
@@ -1,6 +1,8 @@
-use std::sync::{OnceLock, Arc};
+use std::{
+  sync::{Arc, OnceLock},
+  time::{SystemTime, UNIX_EPOCH}
+};
 
-use regex::Regex;
 use rust_i18n::t;
 use serenity::{
   model::{
@@ -10,11 +12,20 @@ use serenity::{
         InteractionResponseType
       },
       Message,
-      Reaction
+      MessageType,
+      Reaction,
+      GuildId,
+      ChannelId,
+      Channel,
+      ChannelType
     },
     user::User
   },
   prelude::Context,
+  http::{
+    request::{Request, RequestBuilder},
+    routing::RouteInfo
+  },
   builder::{
     GetMessages,
     EditMessage,
@@ -24,21 +35,43 @@ use serenity::{
   },
   Error, utils::Color,
 };
+use serde::Deserialize;
+use tokio::sync::Mutex;
 use anyhow::Result;
-use tracing::log::error;
+use tracing::log::{error, debug};
 
-use super::oauth::TwitterClient;
+use super::{
+  oauth::TwitterClient,
+  link::{LinkScope, resolve_tweet_ids},
+  ttl::TtlCache,
+  lock::{KeyedLock, KeyedGuard}
+};
 
 
-static TWITTER_POST_ID_REGEX: OnceLock<Regex> = OnceLock::new();
+pub static BOT_USER_ID: OnceLock<u64> = OnceLock::new();
 
 pub static EMBED_INFO_COLOR: u32 = 0x3983f2;
 pub static EMBED_ERROR_COLOR: u32 = 0xeca42c;
 
+static GUILD_LOCALE_MAX_AGE: u64 = 86400;
+static GUILD_LOCALE_MAX_ITEM: usize = 1000;
+static GUILD_LOCALES: OnceLock<Mutex<TtlCache<u64, String>>> = OnceLock::new();
+static CHANNEL_PARENT_MAX_AGE: u64 = 86400;
+static CHANNEL_PARENT_MAX_ITEM: usize = 10000;
+static CHANNEL_PARENTS: OnceLock<Mutex<TtlCache<u64, u64>>> = OnceLock::new();
+static MESSAGE_LOCKS: OnceLock<KeyedLock<u64>> = OnceLock::new();
+
+
+#[derive(Debug, Deserialize)]
+struct GuildLocale {
+  preferred_locale: String
+}
+
 
 pub async fn process_reaction(
   context: &Context,
-  reaction: &Reaction
+  reaction: &Reaction,
+  scope: LinkScope
 ) -> Option<(TwitterClient, Arc<str>)> {
   let user: User = match reaction.user(&context.http).await {
     Ok(user) => user,
@@ -54,12 +87,16 @@ pub async fn process_reaction(
     Err(why) => { error!("{:?}", why); return None; }
   };
   
-  let tweet_id: Arc<str> = match get_first_tweet_id(&message.content) {
-    Some(tweet_id) => tweet_id,
-    None => return None
+  let tweet_id: Arc<str> = match collect_tweet_ids(context, &message, scope).await.as_slice() {
+    [tweet_id] => tweet_id.clone(),
+    [] => return None,
+    tweet_ids => {
+      debug!("Ignored reaction on {} tweets | Message: {}", tweet_ids.len(), message.id.as_u64());
+      return None;
+    }
   };
 
-  let twitter_client: TwitterClient = match TwitterClient::get_client(&context, user).await {
+  let twitter_client: TwitterClient = match TwitterClient::get_client(&context, user, reaction.guild_id).await {
     Ok(twitter_client) => twitter_client,
     Err(why) => { error!("{:?}", why); return None; }
   };
@@ -67,22 +104,90 @@ pub async fn process_reaction(
   Some((twitter_client, tweet_id))
 }
 
-pub fn get_first_tweet_id(message: &str) -> Option<Arc<str>> {
-  let tweet_id: Arc<str> = TWITTER_POST_ID_REGEX.get_or_init(
-    || {
-      Regex::new(
-        r#"(?ms).*(?:https|http)://(?:www\.)?(?:twitter|fxtwitter|vxtwitter|x)\.com/[A-Za-z0-9_]{1,15}/status/(?P<tweet_id>[0-9]*).*"#
-      ).expect("Regex init failed.")
-    }
+pub async fn collect_tweet_ids(
+  context: &Context,
+  message: &Message,
+  scope: LinkScope
+) -> Vec<Arc<str>> {
+  let tweet_ids: Vec<Arc<str>> = resolve_tweet_ids(&get_linked_text(message, scope)).await;
+
+  if !tweet_ids.is_empty() {
+    return tweet_ids;
+  }
+
+  match get_referenced_message(context, message).await {
+    Some(referenced_message) => resolve_tweet_ids(&get_linked_text(&referenced_message, scope)).await,
+    None => tweet_ids
+  }
+}
+
+pub fn is_own_message(message: &Message) -> bool {
+  BOT_USER_ID
+    .get()
+    .is_some_and(|user_id: &u64| user_id == message.author.id.as_u64())
+}
+
+pub async fn lock_message(message: &Message) -> KeyedGuard<'static, u64> {
+  MESSAGE_LOCKS
+    .get_or_init(KeyedLock::new)
+    .lock(*message.id.as_u64())
+    .await
+}
+
+pub async fn get_own_replies(context: &Context, message: &Message) -> Result<Vec<Message>> {
+  Ok(
+    message.channel_id.messages(
+      &context.http,
+      |retriever: &mut GetMessages| retriever.after(message.id)
+    ).await?
+      .into_iter()
+      .filter(
+        |reply: &Message| is_own_message(reply)
+          && reply.message_reference.as_ref().and_then(|reference| reference.message_id) == Some(message.id)
+      )
+      .collect()
   )
-    .replace(message, "$tweet_id")
-    .into();
+}
+
+fn get_linked_text(message: &Message, scope: LinkScope) -> String {
+  let mut linked_text: String = scope.filter(&message.content);
+
+  for url in message.embeds.iter().filter_map(|embed| embed.url.as_deref()) {
+    linked_text.push('\n');
+    linked_text.push_str(url);
+  }
 
-  if tweet_id.len() == message.len() {
+  linked_text
+}
+
+async fn get_referenced_message(
+  context: &Context,
+  message: &Message
+) -> Option<Message> {
+  if !matches!(message.kind, MessageType::Regular | MessageType::InlineReply) {
     return None;
   }
 
-  Some(tweet_id)
+  if let Some(referenced_message) = &message.referenced_message {
+    return Some(*referenced_message.clone());
+  }
+
+  let (channel_id, message_id) = match &message.message_reference {
+    Some(reference) => (reference.channel_id, reference.message_id?),
+    None => return None
+  };
+
+  match channel_id.message(&context.http, message_id).await {
+    Ok(referenced_message) => Some(referenced_message),
+    Err(why) => { debug!("Fetch referenced message error: {:?}", why); None }
+  }
+}
+
+pub fn current_time() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .expect("Epoch fail!")
+    .as_secs()
 }
 
 pub fn match_locale(discord_locale: &str) -> String {
@@ -93,6 +198,69 @@ pub fn match_locale(discord_locale: &str) -> String {
   }
 }
 
+pub async fn get_guild_locale(context: &Context, guild_id: Option<GuildId>) -> String {
+  let guild_id: u64 = match guild_id {
+    Some(guild_id) => *guild_id.as_u64(),
+    None => return match_locale("")
+  };
+
+  let guild_locales: &Mutex<TtlCache<u64, String>> = GUILD_LOCALES.get_or_init(
+    || Mutex::new(TtlCache::new(GUILD_LOCALE_MAX_AGE, GUILD_LOCALE_MAX_ITEM))
+  );
+
+  if let Some(locale) = guild_locales.lock().await.get(&guild_id) {
+    return locale.clone();
+  }
+
+  let locale: String = match context.http.fire::<GuildLocale>(
+    Request::new(RequestBuilder::new(RouteInfo::GetGuild { guild_id }))
+  ).await {
+    Ok(guild) => match_locale(&guild.preferred_locale),
+    Err(why) => {
+      debug!("Fetch guild locale error: {:?} | Guild: {}", why, guild_id);
+      return match_locale("");
+    }
+  };
+
+  guild_locales
+    .lock()
+    .await
+    .insert(guild_id, locale.clone());
+
+  locale
+}
+
+pub async fn get_parent_channel_id(context: &Context, channel_id: ChannelId) -> u64 {
+  let channel_parents: &Mutex<TtlCache<u64, u64>> = CHANNEL_PARENTS.get_or_init(
+    || Mutex::new(TtlCache::new(CHANNEL_PARENT_MAX_AGE, CHANNEL_PARENT_MAX_ITEM))
+  );
+
+  if let Some(parent_id) = channel_parents.lock().await.get(channel_id.as_u64()) {
+    return *parent_id;
+  }
+
+  let parent_id: u64 = match channel_id.to_channel(&context.http).await {
+    Ok(Channel::Guild(channel)) => match channel.kind {
+      ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread => *channel.parent_id
+        .unwrap_or(channel_id)
+        .as_u64(),
+      _ => *channel_id.as_u64()
+    },
+    Ok(_) => *channel_id.as_u64(),
+    Err(why) => {
+      debug!("Fetch channel error: {:?} | Channel: {}", why, channel_id.as_u64());
+      return *channel_id.as_u64();
+    }
+  };
+
+  channel_parents
+    .lock()
+    .await
+    .insert(*channel_id.as_u64(), parent_id);
+
+  parent_id
+}
+
 pub async fn check_dm(
   context: &Context,
   interaction: &ApplicationCommandInteraction
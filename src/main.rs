@@ -1,8 +1,10 @@
 mod command;
 mod core;
+#[cfg(test)]
+mod tests;
 
 
-use std::{env, time::Duration};
+use std::{env, sync::Arc, time::Duration};
 
 use dotenv::dotenv;
 use rust_i18n::i18n;
@@ -19,6 +21,8 @@ use serenity::{
     command::Command,
     Reaction,
     Message,
+    MessageUpdateEvent,
+    MessageReaction,
     ReactionType,
     Activity
   },
@@ -32,9 +36,24 @@ use tokio::{task, time::sleep};
 use tracing::{Level, log::{info, error}};
 use anyhow::{Result, anyhow};
 
+use crate::command::compose::ComposeAction;
 use crate::core::{
-  utils::{get_first_tweet_id, match_locale, process_reaction},
-  cache::{AccessTokenCache, MAX_AGE}
+  utils::{BOT_USER_ID, match_locale, process_reaction, collect_tweet_ids, is_own_message, lock_message, get_own_replies, get_parent_channel_id},
+  lock::KeyedGuard,
+  cache::{AccessTokenCache, TweetCache, MAX_AGE},
+  oauth::get_bearer_token,
+  oauth2::check_oauth2_config,
+  action::TweetAction,
+  settings::{GuildSettingsStore, GuildSettings, ActionMode},
+  error::TwitterError,
+  expiry::{DISCORD_HTTP, validate_tokens, get_token_validation_interval},
+  callback::{CallbackServer, DEFAULT_CALLBACK_ADDRESS, get_callback_url}
+};
+#[cfg(feature = "mock")]
+use crate::core::{
+  oauth::{TWITTER_API_BASE_URL, TWITTER_BEARER_TOKEN, get_consumer_key, get_consumer_secret},
+  oauth2::TWITTER_OAUTH2_AUTHORIZE_URL,
+  mock::{MockTwitterServer, MOCK_APP_BEARER_TOKEN}
 };
 
 
@@ -50,24 +69,28 @@ impl EventHandler for Handler {
     context: Context,
     reaction: Reaction
   ) {
-    let (mut twitter_client, tweet_id) = match process_reaction(&context, &reaction).await {
-      Some((twitter_client, tweet_id)) => (twitter_client, tweet_id),
+    let action: TweetAction = match TweetAction::from_emoji(reaction.emoji.as_data().as_str()) {
+      Some(action) => action,
       None => return
     };
 
-    let result: Result<()> = match reaction.emoji.as_data().as_str() {
-      "❤️" => twitter_client.like(&tweet_id).await,
-      "🔁" => twitter_client.retweet(&tweet_id).await,
-      "📡" => match twitter_client.get_author_id(&tweet_id).await {
-        Ok(author_id) => twitter_client.follow(&author_id).await,
-        Err(why) => { error!("{:?}", why); return; }
-      },
-      _ => return
+    let settings: GuildSettings = GuildSettingsStore::get().resolve(reaction.guild_id).await;
+
+    if !settings.is_action_enabled(action) || !settings.is_channel_allowed(get_parent_channel_id(&context, reaction.channel_id).await) {
+      return;
+    }
+
+    let (mut twitter_client, tweet_id) = match process_reaction(&context, &reaction, settings.link_scope()).await {
+      Some((twitter_client, tweet_id)) => (twitter_client, tweet_id),
+      None => return
     };
 
+    let result: Result<bool> = action.invoke(&mut twitter_client, &tweet_id).await;
+
     info!(
-      "Invoke action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id}",
-      action = reaction.emoji.as_data().as_str(),
+      "Invoke action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id} | Applied: {applied}",
+      action = action.emoji(),
+      applied = result.as_ref().is_ok_and(|applied: &bool| *applied),
       tweet_id = tweet_id,
       user_id = match reaction.user_id {
         Some(user_id) => *user_id.as_u64(),
@@ -81,6 +104,17 @@ impl EventHandler for Handler {
 
     if let Err(why) = result {
       error!("ReactionAdd error: {}", why);
+
+      if let Some(user_id) = reaction.user_id {
+        action.record_failure(*user_id.as_u64(), &tweet_id, &why);
+      }
+
+      match TwitterError::classify(&why) {
+        TwitterError::Revoked => (),
+        error => if let Err(why) = error.notify_reaction(&context, &reaction).await {
+          error!("Send error notice failed: {:?}", why);
+        }
+      }
     }
   }
 
@@ -89,24 +123,28 @@ impl EventHandler for Handler {
     context: Context,
     reaction: Reaction
   ) {
-    let (mut twitter_client, tweet_id) = match process_reaction(&context, &reaction).await {
-      Some((twitter_client, tweet_id)) => (twitter_client, tweet_id),
+    let action: TweetAction = match TweetAction::from_emoji(reaction.emoji.as_data().as_str()) {
+      Some(action) => action,
       None => return
     };
 
-    let result: Result<()> = match reaction.emoji.as_data().as_str() {
-      "❤️" => twitter_client.unlike(&tweet_id).await,
-      "🔁" => twitter_client.unretweet(&tweet_id).await,
-      "📡" => match twitter_client.get_author_id(&tweet_id).await {
-        Ok(author_id) => twitter_client.unfollow(&author_id).await,
-        Err(why) => { error!("{:?}", why); return; }
-      },
-      _ => return
+    let settings: GuildSettings = GuildSettingsStore::get().resolve(reaction.guild_id).await;
+
+    if !settings.is_action_enabled(action) || !settings.is_channel_allowed(get_parent_channel_id(&context, reaction.channel_id).await) {
+      return;
+    }
+
+    let (mut twitter_client, tweet_id) = match process_reaction(&context, &reaction, settings.link_scope()).await {
+      Some((twitter_client, tweet_id)) => (twitter_client, tweet_id),
+      None => return
     };
 
+    let result: Result<bool> = action.revoke(&mut twitter_client, &tweet_id).await;
+
     info!(
-      "Revoke action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id}",
-      action = reaction.emoji.as_data().as_str(),
+      "Revoke action `{action}` | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id} | Applied: {applied}",
+      action = action.emoji(),
+      applied = result.as_ref().is_ok_and(|applied: &bool| *applied),
       tweet_id = tweet_id,
       user_id = match reaction.user_id {
         Some(user_id) => *user_id.as_u64(),
@@ -120,6 +158,17 @@ impl EventHandler for Handler {
 
     if let Err(why) = result {
       error!("ReactionRemove error: {}", why);
+
+      if let Some(user_id) = reaction.user_id {
+        action.record_failure(*user_id.as_u64(), &tweet_id, &why);
+      }
+
+      match TwitterError::classify(&why) {
+        TwitterError::Revoked => (),
+        error => if let Err(why) = error.notify_reaction(&context, &reaction).await {
+          error!("Send error notice failed: {:?}", why);
+        }
+      }
     }
   }
 
@@ -128,6 +177,12 @@ impl EventHandler for Handler {
     context: Context,
     message: Message
   ) {
+    if is_own_message(&message) {
+      return;
+    }
+
+    let _message_lock: KeyedGuard<'static, u64> = lock_message(&message).await;
+
     match message.channel(&context.http).await {
       Ok(channel) => {
         if channel.private().is_some() {
@@ -137,30 +192,182 @@ impl EventHandler for Handler {
       Err(why) => { error!("Fetch channel error: {:?}", why); return; }
     }
 
-    match get_first_tweet_id(&message.content) {
-      Some(tweet_id) => {
-        for emoji in ["❤️", "🔁", "📡"] {
-          match message.react(
-            &context.http,
-            ReactionType::Unicode(emoji.to_string())
-          ).await {
-            Ok(_) => (),
-            Err(why) => { error!("Apply reaction error: {:?}", why); return; }
-          }
+    let settings: GuildSettings = GuildSettingsStore::get().resolve(message.guild_id).await;
+
+    if !settings.auto_reactions || !settings.is_channel_allowed(get_parent_channel_id(&context, message.channel_id).await) {
+      return;
+    }
+
+    let actions: Vec<TweetAction> = settings.enabled_actions();
+
+    if actions.is_empty() {
+      return;
+    }
+
+    let mut tweet_ids: Vec<Arc<str>> = collect_tweet_ids(&context, &message, settings.link_scope()).await;
+
+    if settings.previews && get_bearer_token().is_some() {
+      tweet_ids = command::preview::apply_previews(&context, &message, tweet_ids, 0, settings.mode, &actions).await;
+    }
+
+    if tweet_ids.is_empty() {
+      return;
+    }
+
+    if settings.mode == ActionMode::Buttons || tweet_ids.len() > 1 {
+      if let Err(why) = command::button::send_buttons(&context, &message, &tweet_ids, &actions).await {
+        error!("Apply buttons error: {:?}", why);
+        return;
+      }
+
+      info!(
+        "Applied buttons | Tweet: {tweet_ids} | User: {user_id} | Guild: {guild_id}",
+        tweet_ids = tweet_ids.join(", "),
+        user_id = message.author.id.as_u64(),
+        guild_id = match message.guild_id {
+          Some(guild_id) => *guild_id.as_u64(),
+          None => 0
+        },
+      )
+    } else {
+      for action in actions {
+        match message.react(
+          &context.http,
+          ReactionType::Unicode(action.emoji().to_string())
+        ).await {
+          Ok(_) => (),
+          Err(why) => { error!("Apply reaction error: {:?}", why); return; }
         }
+      }
 
-        info!(
-          "Applied reaction | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id}",
-          tweet_id = tweet_id,
-          user_id = message.author.id.as_u64(),
-          guild_id = match message.guild_id {
-            Some(guild_id) => *guild_id.as_u64(),
-            None => 0
-          },
+      info!(
+        "Applied reaction | Tweet: {tweet_id} | User: {user_id} | Guild: {guild_id}",
+        tweet_id = tweet_ids[0],
+        user_id = message.author.id.as_u64(),
+        guild_id = match message.guild_id {
+          Some(guild_id) => *guild_id.as_u64(),
+          None => 0
+        },
+      )
+    }
+  }
+
+  async fn message_update(
+    self: &Self,
+    context: Context,
+    event: MessageUpdateEvent
+  ) {
+    if event.edited_timestamp.is_none() || event.content.is_none() {
+      return;
+    }
+
+    if event.guild_id.is_none() {
+      return;
+    }
+
+    let settings: GuildSettings = GuildSettingsStore::get().resolve(event.guild_id).await;
+
+    if !settings.auto_reactions || !settings.is_channel_allowed(get_parent_channel_id(&context, event.channel_id).await) {
+      return;
+    }
+
+    let message: Message = match event.channel_id.message(&context.http, event.id).await {
+      Ok(message) => message,
+      Err(why) => { error!("Fetch message error: {:?}", why); return; }
+    };
+
+    if is_own_message(&message) {
+      return;
+    }
+
+    let _message_lock: KeyedGuard<'static, u64> = lock_message(&message).await;
+
+    let replies: Vec<Message> = match get_own_replies(&context, &message).await {
+      Ok(replies) => replies,
+      Err(why) => { error!("Fetch replies error: {:?}", why); return; }
+    };
+
+    let actions: Vec<TweetAction> = settings.enabled_actions();
+    let previewed_ids: Vec<Arc<str>> = command::preview::get_previewed_ids(&replies);
+
+    let mut tweet_ids: Vec<Arc<str>> = match actions.is_empty() {
+      true => Vec::new(),
+      false => collect_tweet_ids(&context, &message, settings.link_scope()).await
+    };
+    tweet_ids.retain(|tweet_id: &Arc<str>| !previewed_ids.contains(tweet_id));
+
+    if settings.previews && get_bearer_token().is_some() {
+      tweet_ids = command::preview::apply_previews(
+        &context,
+        &message,
+        tweet_ids,
+        previewed_ids.len(),
+        settings.mode,
+        &actions
+      ).await;
+    }
+
+    let use_buttons: bool = !tweet_ids.is_empty() && (settings.mode == ActionMode::Buttons || tweet_ids.len() > 1);
+
+    let reactions: Vec<TweetAction> = match use_buttons || tweet_ids.is_empty() {
+      true => Vec::new(),
+      false => actions.clone()
+    };
+
+    let reacted_actions: Vec<TweetAction> = TweetAction::ALL
+      .into_iter()
+      .filter(
+        |action: &TweetAction| message.reactions.iter().any(
+          |reaction: &MessageReaction| reaction.me && reaction.reaction_type == ReactionType::Unicode(action.emoji().to_string())
         )
-      },
-      None => ()
+      )
+      .collect();
+
+    for action in TweetAction::ALL {
+      let result: Result<(), serenity::Error> = match (reactions.contains(&action), reacted_actions.contains(&action)) {
+        (true, false) => message.react(
+          &context.http,
+          ReactionType::Unicode(action.emoji().to_string())
+        ).await.map(|_| ()),
+        (false, true) => message.channel_id.delete_reaction(
+          &context.http,
+          message.id,
+          None,
+          ReactionType::Unicode(action.emoji().to_string())
+        ).await,
+        _ => Ok(())
+      };
+
+      if let Err(why) = result {
+        error!("Update reaction error: {:?} | Action: {}", why, action.emoji());
+      }
     }
+
+    let result: Result<bool> = match (use_buttons, command::button::find_buttons(&replies)) {
+      (true, Some(mut buttons)) => command::button::edit_buttons(&context, &mut buttons, &tweet_ids, &actions).await.map(|_| false),
+      (true, None) => command::button::send_buttons(&context, &message, &tweet_ids, &actions).await.map(|_| true),
+      (false, Some(buttons)) => buttons.delete(&context.http).await.map(|_| true).map_err(anyhow::Error::from),
+      (false, None) => Ok(false)
+    };
+
+    let buttons_changed: bool = match result {
+      Ok(buttons_changed) => buttons_changed,
+      Err(why) => { error!("Update buttons error: {:?}", why); return; }
+    };
+
+    if !buttons_changed && reactions == reacted_actions {
+      return;
+    }
+
+    info!(
+      "Updated actions | Tweet: {tweet_ids} | User: {user_id} | Guild: {guild_id}",
+      tweet_ids = tweet_ids.join(", "),
+      user_id = message.author.id.as_u64(),
+      guild_id = match event.guild_id {
+        Some(guild_id) => *guild_id.as_u64(),
+        None => 0
+      },
+    )
   }
 
   async fn interaction_create(
@@ -184,20 +391,75 @@ impl EventHandler for Handler {
       let result: Result<()> = match interaction.data.name.as_str() {
         "connect" => command::connect::execute(&context, &interaction).await,
         "disconnect" => command::disconnect::execute(&context, &interaction).await,
+        "accounts" => command::accounts::execute(&context, &interaction).await,
+        "status" => command::status::execute(&context, &interaction).await,
         "support" => command::support::execute(&context, &interaction).await,
         "invite" => command::invite::execute(&context, &interaction).await,
+        "settings" => command::settings::execute(&context, &interaction).await,
+        "Reply on Twitter" => command::compose::execute(&context, &interaction, ComposeAction::Reply).await,
+        "Quote on Twitter" => command::compose::execute(&context, &interaction, ComposeAction::Quote).await,
         _ => Err(anyhow!("Interaction not found."))
       };
 
       if let Err(why) = result {
         error!("ApplicationCommand error: {:?}", why);
       }
+    } else if let Interaction::MessageComponent(mut interaction) = interaction {
+      info!(
+        "Received MessageComponent `{custom_id}` | User: {user_id} | Guild: {guild_id}",
+        custom_id = interaction.data.custom_id,
+        guild_id = match interaction.guild_id {
+          Some(guild_id) => *guild_id.as_u64(),
+          None => 0
+        },
+        user_id = interaction.user.id.as_u64()
+      );
+
+      interaction.locale = match_locale(&interaction.locale);
+
+      let result: Result<()> = match interaction.data.custom_id.split_once(":") {
+        Some(("action", _)) => command::button::execute(&context, &interaction).await,
+        Some(("compose-select", _)) => command::compose::select(&context, &interaction).await,
+        _ => Err(anyhow!("Component not found."))
+      };
+
+      if let Err(why) = result {
+        error!("MessageComponent error: {:?}", why);
+      }
+    } else if let Interaction::ModalSubmit(mut interaction) = interaction {
+      info!(
+        "Received ModalSubmit `{custom_id}` | User: {user_id} | Guild: {guild_id}",
+        custom_id = interaction.data.custom_id,
+        guild_id = match interaction.guild_id {
+          Some(guild_id) => *guild_id.as_u64(),
+          None => 0
+        },
+        user_id = interaction.user.id.as_u64()
+      );
+
+      interaction.locale = match_locale(&interaction.locale);
+
+      if let Err(why) = command::compose::submit(&context, &interaction).await {
+        error!("ModalSubmit error: {:?}", why);
+      }
+    } else if let Interaction::Autocomplete(interaction) = interaction {
+      let result: Result<()> = match interaction.data.name.as_str() {
+        "accounts" => command::accounts::autocomplete(&context, &interaction).await,
+        _ => Err(anyhow!("Autocomplete not found."))
+      };
+
+      if let Err(why) = result {
+        error!("Autocomplete error: {:?}", why);
+      }
     }
   }
 
   async fn ready(self: &Self, context: Context, ready: Ready) {
     info!("Logged in as `{}#{}`", ready.user.name, ready.user.discriminator);
 
+    BOT_USER_ID.get_or_init(|| *ready.user.id.as_u64());
+    DISCORD_HTTP.get_or_init(|| context.http.clone());
+
     let commands: Vec<Command> = Command::set_global_application_commands(
       &context.http,
       |commands: &mut CreateApplicationCommands| {
@@ -212,6 +474,16 @@ impl EventHandler for Handler {
               command::disconnect::register(command)
             }
           )
+          .create_application_command(
+            |command: &mut CreateApplicationCommand| {
+              command::accounts::register(command)
+            }
+          )
+          .create_application_command(
+            |command: &mut CreateApplicationCommand| {
+              command::status::register(command)
+            }
+          )
           .create_application_command(
             |command: &mut CreateApplicationCommand| {
               command::support::register(command)
@@ -222,6 +494,21 @@ impl EventHandler for Handler {
               command::invite::register(command)
             }
           )
+          .create_application_command(
+            |command: &mut CreateApplicationCommand| {
+              command::settings::register(command)
+            }
+          )
+          .create_application_command(
+            |command: &mut CreateApplicationCommand| {
+              command::compose::register_reply(command)
+            }
+          )
+          .create_application_command(
+            |command: &mut CreateApplicationCommand| {
+              command::compose::register_quote(command)
+            }
+          )
       }
     ).await.expect("Register commands failed.");
 
@@ -248,6 +535,44 @@ async fn main() {
   let token: String = env::var("DISCORD_BOT_TOKEN")
     .expect("DISCORD_BOT_TOKEN is not set.");
 
+  AccessTokenCache::get();
+
+  check_oauth2_config().expect("Invalid OAuth 2.0 configuration.");
+
+  #[cfg(feature = "mock")]
+  if let Ok(mock_address) = env::var("TWITTER_MOCK_SERVER_ADDRESS") {
+    let mock_server: MockTwitterServer = MockTwitterServer::start(
+      mock_address.parse().expect("Invalid TWITTER_MOCK_SERVER_ADDRESS."),
+      get_consumer_key(),
+      get_consumer_secret()
+    ).await.expect("Start mock server failed.");
+
+    info!("Mock Twitter server listening on `{}`", mock_server.base_url());
+
+    TWITTER_API_BASE_URL
+      .set(mock_server.base_url())
+      .expect("Twitter API base URL already initialized.");
+
+    TWITTER_OAUTH2_AUTHORIZE_URL
+      .set(format!("{}/i/oauth2/authorize", mock_server.base_url()).into())
+      .expect("Twitter OAuth 2.0 authorize URL already initialized.");
+
+    TWITTER_BEARER_TOKEN
+      .set(Some(MOCK_APP_BEARER_TOKEN.into()))
+      .expect("Twitter bearer token already initialized.");
+  }
+
+  if get_callback_url().is_some() {
+    let callback_server: CallbackServer = CallbackServer::start(
+      env::var("OAUTH_CALLBACK_ADDRESS")
+        .unwrap_or(DEFAULT_CALLBACK_ADDRESS.to_string())
+        .parse()
+        .expect("Invalid OAUTH_CALLBACK_ADDRESS.")
+    ).await.expect("Start callback server failed.");
+
+    info!("OAuth callback server listening on `{}`", callback_server.address());
+  }
+
   let intents: GatewayIntents = GatewayIntents::DIRECT_MESSAGES
     | GatewayIntents::GUILD_MEMBERS
     | GatewayIntents::GUILD_MESSAGE_REACTIONS
@@ -259,10 +584,24 @@ async fn main() {
       loop {
         sleep(Duration::from_secs(MAX_AGE / 2)).await;
         AccessTokenCache::get().clean_up().await;
+        TweetCache::get().clean_up().await;
       }
     }
   );
 
+  let token_validation_interval: u64 = get_token_validation_interval();
+
+  if token_validation_interval > 0 {
+    task::spawn(
+      async move {
+        loop {
+          sleep(Duration::from_secs(token_validation_interval)).await;
+          validate_tokens().await;
+        }
+      }
+    );
+  }
+
   let mut client: Client = Client::builder(token, intents)
     .event_handler(Handler)
     .await
@@ -0,0 +1,374 @@
+use std::{
+  env,
+  io::Read,
+  sync::{Arc, OnceLock, mpsc},
+  thread
+};
+
+use flate2::read::GzDecoder;
+use hyper::{Client, body};
+use tokio::runtime::Runtime;
+use anyhow::Result;
+
+use crate::core::{
+  mock::MockTwitterServer,
+  oauth::{TwitterClient, TWITTER_API_BASE_URL},
+  oauth2::{OAuth2Client, AuthorizationRequest, TWITTER_OAUTH2_AUTHORIZE_URL},
+  store::{Credential, StoredToken, StoredAccounts},
+  cache::AccessTokenCache,
+  action::{TweetAction, ToggleResult},
+  model::{TweetPreview, TwitterUser},
+  error::TwitterError
+};
+
+
+static CONSUMER_KEY: &str = "test-consumer-key";
+static CONSUMER_SECRET: &str = "test-consumer-secret";
+
+static MOCK_BASE_URL: OnceLock<Arc<str>> = OnceLock::new();
+
+
+fn set_up() {
+  MOCK_BASE_URL.get_or_init(
+    || {
+      env::set_var("TWITTER_CONSUMER_KEY", CONSUMER_KEY);
+      env::set_var("TWITTER_CONSUMER_SECRET", CONSUMER_SECRET);
+      env::set_var("TWITTER_CLIENT_ID", "test-client-id");
+      env::set_var("TWITTER_OAUTH2_REDIRECT_URI", "http://localhost/callback");
+      env::set_var("TOKEN_STORE_KEY", "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+      env::set_var(
+        "DATABASE_PATH",
+        env::temp_dir().join(format!("twitcord-test-{}", std::process::id()))
+      );
+
+      let (sender, receiver) = mpsc::channel::<Arc<str>>();
+
+      thread::spawn(
+        move || {
+          Runtime::new().expect("Start mock runtime failed.").block_on(
+            async move {
+              let mock_server: MockTwitterServer = MockTwitterServer::start(
+                "127.0.0.1:0".parse().expect("Invalid mock address."),
+                CONSUMER_KEY.into(),
+                CONSUMER_SECRET.into()
+              ).await.expect("Start mock server failed.");
+
+              sender.send(mock_server.base_url()).expect("Send mock base URL failed.");
+
+              std::future::pending::<()>().await;
+            }
+          )
+        }
+      );
+
+      let base_url: Arc<str> = receiver.recv().expect("Receive mock base URL failed.");
+
+      TWITTER_API_BASE_URL.get_or_init(|| base_url.clone());
+      TWITTER_OAUTH2_AUTHORIZE_URL.get_or_init(|| format!("{}/i/oauth2/authorize", base_url).into());
+
+      base_url
+    }
+  );
+}
+
+async fn read_authorize_page(url: &str) -> Result<String> {
+  let encoded: body::Bytes = body::to_bytes(
+    Client::new()
+      .get(url.parse()?)
+      .await?
+      .into_body()
+  ).await?;
+
+  let mut page: String = String::new();
+  GzDecoder::new(&*encoded).read_to_string(&mut page)?;
+
+  Ok(
+    page
+      .split("<code>")
+      .nth(1)
+      .and_then(|code: &str| code.split("</code>").next())
+      .unwrap_or_default()
+      .to_string()
+  )
+}
+
+async fn connect_oauth1() -> Result<Credential> {
+  let mut twitter_client: TwitterClient = TwitterClient::new(None, None)?;
+  let authorization_url: Arc<str> = twitter_client.get_authorization_url().await?;
+  let pin: String = read_authorize_page(&authorization_url).await?;
+
+  let (access_token, access_token_secret): (&str, &str) = twitter_client.get_access_token(&pin).await?;
+
+  Ok(
+    Credential::OAuth1 {
+      access_token: access_token.into(),
+      access_token_secret: access_token_secret.into()
+    }
+  )
+}
+
+async fn connect_oauth2() -> Result<Credential> {
+  let mut oauth2_client: OAuth2Client = OAuth2Client::new()?;
+  let request: AuthorizationRequest = oauth2_client.authorization_request();
+  let redirect_url: String = read_authorize_page(&request.url).await?;
+
+  let code: &str = redirect_url
+    .split("code=")
+    .nth(1)
+    .and_then(|code: &str| code.split('&').next())
+    .unwrap_or_default();
+
+  oauth2_client.exchange_code(code, &request.code_verifier).await
+}
+
+async fn store_account(user_id: u64, credential: Credential) -> Result<StoredToken> {
+  let account: TwitterUser = TwitterClient::from_credential(&credential)?.get_me().await?;
+  let token: StoredToken = StoredToken::new(credential, account.id, account.username, "en".into());
+
+  AccessTokenCache::get().add(user_id, token.clone()).await?;
+
+  Ok(token)
+}
+
+async fn like_count(tweet_id: &str) -> Result<u64> {
+  let credential: Credential = connect_oauth1().await?;
+  let preview: TweetPreview = TwitterClient::from_credential(&credential)?
+    .get_tweet_preview(tweet_id)
+    .await?;
+
+  Ok(preview.like_count)
+}
+
+
+#[tokio::test]
+async fn oauth1_connect_and_react() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1001;
+  let tweet_id: &str = "1001001";
+
+  let token: StoredToken = store_account(user_id, connect_oauth1().await?).await?;
+  assert!(token.is_identified());
+
+  let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, &token).await?;
+
+  assert!(TweetAction::Like.invoke(&mut twitter_client, tweet_id).await?);
+  assert_eq!(like_count(tweet_id).await?, 1);
+  assert!(TweetAction::Like.revoke(&mut twitter_client, tweet_id).await?);
+  assert_eq!(like_count(tweet_id).await?, 0);
+
+  assert!(TweetAction::Retweet.invoke(&mut twitter_client, tweet_id).await?);
+  assert!(TweetAction::Retweet.revoke(&mut twitter_client, tweet_id).await?);
+
+  assert!(TweetAction::Follow.invoke(&mut twitter_client, tweet_id).await?);
+  assert!(TweetAction::Follow.revoke(&mut twitter_client, tweet_id).await?);
+
+  let bookmarked: anyhow::Error = TweetAction::Bookmark
+    .invoke(&mut twitter_client, tweet_id)
+    .await
+    .unwrap_err();
+  assert_eq!(TwitterError::classify(&bookmarked), TwitterError::Unsupported);
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn oauth1_toggle_round_trip() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1002;
+  let tweet_id: &str = "1002002";
+
+  let token: StoredToken = store_account(user_id, connect_oauth1().await?).await?;
+  let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, &token).await?;
+
+  assert_eq!(
+    TweetAction::Like.toggle(&mut twitter_client, user_id, tweet_id).await?,
+    ToggleResult::Invoked(true)
+  );
+  assert_eq!(
+    TweetAction::Like.toggle(&mut twitter_client, user_id, tweet_id).await?,
+    ToggleResult::Revoked(true)
+  );
+  assert_eq!(like_count(tweet_id).await?, 0);
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn missing_tweet_is_classified() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1003;
+
+  let token: StoredToken = store_account(user_id, connect_oauth1().await?).await?;
+  let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, &token).await?;
+
+  let missing: anyhow::Error = TweetAction::Like
+    .invoke(&mut twitter_client, "1003404")
+    .await
+    .unwrap_err();
+  assert_eq!(TwitterError::classify(&missing), TwitterError::TweetNotFound);
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn oauth2_connect_refresh_and_bookmark() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1004;
+  let tweet_id: &str = "1004004";
+
+  let credential: Credential = match connect_oauth2().await? {
+    Credential::OAuth2 { access_token, refresh_token, scope, .. } => Credential::OAuth2 {
+      access_token,
+      refresh_token,
+      expires_at: 0,
+      scope
+    },
+    credential => credential
+  };
+
+  let token: StoredToken = store_account(user_id, credential).await?;
+  let mut twitter_client: TwitterClient = TwitterClient::from_token(user_id, &token).await?;
+
+  let accounts: StoredAccounts = AccessTokenCache::get().load(user_id).await?.unwrap_or_default();
+  let refreshed: &StoredToken = accounts.find(&token.account_id).expect("Account not stored.");
+  assert_ne!(refreshed.credential.access_token(), token.credential.access_token());
+
+  assert!(twitter_client.supports(TweetAction::Bookmark));
+  assert!(TweetAction::Bookmark.invoke(&mut twitter_client, tweet_id).await?);
+  assert!(TweetAction::Bookmark.revoke(&mut twitter_client, tweet_id).await?);
+  assert!(TweetAction::Like.invoke(&mut twitter_client, tweet_id).await?);
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn concurrent_refreshes_share_one_grant() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1007;
+
+  let credential: Credential = match connect_oauth2().await? {
+    Credential::OAuth2 { access_token, refresh_token, scope, .. } => Credential::OAuth2 {
+      access_token,
+      refresh_token,
+      expires_at: 0,
+      scope
+    },
+    credential => credential
+  };
+
+  let token: StoredToken = store_account(user_id, credential).await?;
+
+  let (first, second): (Result<TwitterClient>, Result<TwitterClient>) = tokio::join!(
+    TwitterClient::from_token(user_id, &token),
+    TwitterClient::from_token(user_id, &token)
+  );
+  first?;
+  second?;
+
+  assert!(AccessTokenCache::get().load(user_id).await?.is_some());
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn revoked_tokens_are_purged() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1005;
+
+  let revoked: Credential = connect_oauth1().await?;
+  let token: StoredToken = store_account(user_id, revoked.clone()).await?;
+  TwitterClient::revoke_credential(&revoked).await?;
+
+  let error: anyhow::Error = TwitterClient::from_token(user_id, &token)
+    .await?
+    .like("1005005")
+    .await
+    .unwrap_err();
+  assert_eq!(TwitterError::classify(&error), TwitterError::Revoked);
+  assert!(AccessTokenCache::get().load(user_id).await?.is_none());
+
+  Ok(())
+}
+
+#[tokio::test]
+async fn bad_signatures_keep_tokens() -> Result<()> {
+  set_up();
+
+  let user_id: u64 = 1006;
+
+  let access_token: Arc<str> = match connect_oauth1().await? {
+    Credential::OAuth1 { access_token, .. } => access_token,
+    Credential::OAuth2 { access_token, .. } => access_token
+  };
+  let token: StoredToken = StoredToken::new(
+    Credential::OAuth1 {
+      access_token,
+      access_token_secret: "wrong-secret".into()
+    },
+    "".into(),
+    "".into(),
+    "en".into()
+  );
+  AccessTokenCache::get().add(user_id, token.clone()).await?;
+
+  let error: anyhow::Error = TwitterClient::from_token(user_id, &token)
+    .await?
+    .like("1006006")
+    .await
+    .unwrap_err();
+  assert_eq!(TwitterError::classify(&error), TwitterError::Unauthorized);
+  assert!(AccessTokenCache::get().load(user_id).await?.is_some());
+
+  Ok(())
+}
+
+#[test]
+fn unidentified_tokens_are_kept_apart() {
+  let first: StoredToken = StoredToken::unidentified(
+    Credential::OAuth1 { access_token: "first".into(), access_token_secret: "secret".into() },
+    "en".into()
+  );
+  let second: StoredToken = StoredToken::unidentified(
+    Credential::OAuth1 { access_token: "second".into(), access_token_secret: "secret".into() },
+    "en".into()
+  );
+
+  let mut accounts: StoredAccounts = StoredAccounts::default();
+  accounts.insert(first.clone());
+  accounts.insert(second.clone());
+  assert_eq!(accounts.tokens.len(), 2);
+
+  accounts.identify(&second.account_id, "2002".into(), "second".into());
+
+  let identified: &StoredToken = accounts.find("2002").expect("Account not identified.");
+  assert_eq!(identified.credential.access_token().as_ref(), "second");
+  assert_eq!(accounts.active.as_deref(), Some("2002"));
+  assert!(!accounts.find(&first.account_id).expect("Account lost.").is_identified());
+}
+
+#[test]
+fn granted_scope_limits_actions() -> Result<()> {
+  set_up();
+
+  let twitter_client: TwitterClient = TwitterClient::from_credential(
+    &Credential::OAuth2 {
+      access_token: "scoped".into(),
+      refresh_token: None,
+      expires_at: 0,
+      scope: Some("tweet.read users.read like.write".into())
+    }
+  )?;
+
+  assert!(twitter_client.supports(TweetAction::Like));
+  assert!(!twitter_client.supports(TweetAction::Retweet));
+  assert!(!twitter_client.supports(TweetAction::Bookmark));
+  assert!(!twitter_client.supports(TweetAction::Follow));
+
+  Ok(())
+}